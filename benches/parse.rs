@@ -0,0 +1,50 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+use adsbexchange::{parse_adsb, parse_adsb_with, FieldGroups};
+
+const STRIDE: usize = 112;
+const AIRCRAFT: usize = 10_000;
+
+/// A globe-sized frame with every validity bit set, so the full decode path
+/// runs for each record.
+fn frame() -> Vec<u8> {
+    let mut data = vec![0u8; STRIDE * (AIRCRAFT + 1)];
+
+    data[8..12].copy_from_slice(&(STRIDE as u32).to_le_bytes());
+    data[16..20].copy_from_slice(&1u32.to_le_bytes());
+    data[40..44].copy_from_slice(&20240218u32.to_le_bytes());
+
+    for (i, record) in data[STRIDE..].chunks_exact_mut(STRIDE).enumerate() {
+        let i = i as u32;
+
+        record[0..4].copy_from_slice(&(0x400000 + i).to_le_bytes());
+        record[8..12].copy_from_slice(&((i as i32 % 360 - 180) * 1_000_000).to_le_bytes());
+        record[12..16].copy_from_slice(&((i as i32 % 180 - 90) * 1_000_000).to_le_bytes());
+        record[20..22].copy_from_slice(&((i % 1600) as i16).to_le_bytes());
+        record[32..34].copy_from_slice(&0x1200u16.to_le_bytes());
+        record[64] = 0xA3;
+        record[67] = ((i % 13) as u8) << 4;
+        record[73..78].fill(0xff);
+        record[78..86].copy_from_slice(b"TEST123 ");
+        record[88..92].copy_from_slice(b"A320");
+        record[92..98].copy_from_slice(b"D-ABCD");
+        record[105] = (i % 256) as u8;
+    }
+
+    data
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let data = frame();
+
+    let mut group = c.benchmark_group("parse_adsb");
+    group.throughput(Throughput::Elements(AIRCRAFT as u64));
+    group.bench_function("globe", |b| b.iter(|| parse_adsb(black_box(&data)).unwrap()));
+    group.bench_function("globe_positions_only", |b| {
+        b.iter(|| parse_adsb_with(black_box(&data), FieldGroups::NONE).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse);
+criterion_main!(benches);
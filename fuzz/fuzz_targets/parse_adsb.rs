@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Arbitrary bodies must come back as Ok or Err, never as a panic.
+fuzz_target!(|data: &[u8]| {
+    let _ = adsbexchange::parse_adsb(data);
+
+    for aircraft in adsbexchange::iter_aircraft(data) {
+        let _ = aircraft;
+    }
+
+    for frame in adsbexchange::iter_frames(data) {
+        let _ = frame;
+    }
+});
@@ -0,0 +1,859 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::time::{Duration, SystemTime};
+
+use crate::bincraft::BinCraft;
+use crate::category::Category;
+use crate::geo;
+use crate::icao::Icao;
+use crate::squawk::Squawk;
+
+/// Ground speed assumed possible for an aircraft that reports none.
+const MAX_PLAUSIBLE_SPEED_KT: f64 = 1000.0;
+
+/// Movement accepted regardless of elapsed time, for position noise.
+const POSITION_TOLERANCE_NM: f64 = 0.5;
+
+/// Selected altimeter settings accepted as real, in hectopascals. Sea-level
+/// pressure has never been recorded outside about 870 to 1085 hPa, and no
+/// altimeter is set far beyond that; anything else is a corrupt value.
+pub(crate) const PLAUSIBLE_QNH_HPA: RangeInclusive<f32> = 900.0..=1100.0;
+
+/// Hectopascals per inch of mercury.
+const HPA_PER_INHG: f32 = 33.863_89;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SignalType {
+    AdsbIcao,
+    AdsbIcaoNt,
+    AdsrIcao,
+    TisbIcao,
+    Adsc,
+    Mlat,
+    Other,
+    ModeS,
+    AdsbOther,
+    AdsrOther,
+    TisbTrackfile,
+    TisbOther,
+    ModeAc,
+    Unknown,
+}
+
+/// Broad source of a report, used to rank duplicates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignalClass {
+    Adsb,
+    Adsr,
+    Tisb,
+    Mlat,
+    Other,
+}
+
+/// How a position was obtained, from [`Aircraft::position_source`], for
+/// styling positions by how far they can be trusted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PositionSource {
+    /// Broadcast by the aircraft and received directly, with a NIC bounding
+    /// its integrity.
+    Adsb,
+    /// Broadcast by the aircraft and received directly, but with NIC 0: the
+    /// aircraft makes no claim about the position's integrity.
+    AdsbUnverified,
+    /// Broadcast by the aircraft and rebroadcast by a ground station.
+    Adsr,
+    /// Relayed by a ground station from its own surveillance, such as
+    /// radar.
+    Tisb,
+    /// Estimated by multilateration of the aircraft's transmissions.
+    Mlat,
+    /// Any other source, including ADS-C and positions of unknown type.
+    Other,
+}
+
+impl SignalType {
+    pub fn class(&self) -> SignalClass {
+        if self.is_adsb() {
+            SignalClass::Adsb
+        } else if self.is_adsr() {
+            SignalClass::Adsr
+        } else if self.is_tisb() {
+            SignalClass::Tisb
+        } else if matches!(self, SignalType::Mlat) {
+            SignalClass::Mlat
+        } else {
+            SignalClass::Other
+        }
+    }
+
+    /// The name readsb uses for this type in its JSON output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignalType::AdsbIcao => "adsb_icao",
+            SignalType::AdsbIcaoNt => "adsb_icao_nt",
+            SignalType::AdsrIcao => "adsr_icao",
+            SignalType::TisbIcao => "tisb_icao",
+            SignalType::Adsc => "adsc",
+            SignalType::Mlat => "mlat",
+            SignalType::Other => "other",
+            SignalType::ModeS => "mode_s",
+            SignalType::AdsbOther => "adsb_other",
+            SignalType::AdsrOther => "adsr_other",
+            SignalType::TisbTrackfile => "tisb_trackfile",
+            SignalType::TisbOther => "tisb_other",
+            SignalType::ModeAc => "mode_ac",
+            SignalType::Unknown => "unknown",
+        }
+    }
+
+    pub fn is_adsb(&self) -> bool {
+        matches!(
+            self,
+            SignalType::AdsbIcao | SignalType::AdsbIcaoNt | SignalType::AdsbOther
+        )
+    }
+
+    pub fn is_adsr(&self) -> bool {
+        matches!(self, SignalType::AdsrIcao | SignalType::AdsrOther)
+    }
+
+    pub fn is_tisb(&self) -> bool {
+        matches!(
+            self,
+            SignalType::TisbIcao | SignalType::TisbTrackfile | SignalType::TisbOther
+        )
+    }
+}
+
+/// Air/ground state as reported by readsb.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum AirGround {
+    #[default]
+    Invalid,
+    Ground,
+    Airborne,
+    Uncertain,
+}
+
+impl From<u8> for AirGround {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => AirGround::Ground,
+            2 => AirGround::Airborne,
+            3 => AirGround::Uncertain,
+            _ => AirGround::Invalid,
+        }
+    }
+}
+
+/// Integrity and accuracy metrics reported alongside a position.
+///
+/// `sil` is only meaningful together with `sil_type`, which says whether the
+/// probability is given per flight hour or per sample. The NIC/NAC/SIL values
+/// are interpreted according to the transponder's `adsb_version`.
+#[derive(Clone, Default, Debug)]
+pub struct QualityMetrics {
+    /// Navigation Integrity Category.
+    pub nic: u8,
+    /// Radius of containment in meters, derived by readsb from `nic` and the
+    /// NIC supplement bits. `0` means unknown.
+    pub rc: u16,
+    pub nic_a: Option<u8>,
+    pub nic_c: Option<u8>,
+    /// Whether the barometric altitude has been cross-checked against
+    /// another source.
+    pub nic_baro: Option<bool>,
+    /// Navigation Accuracy Category for position.
+    pub nac_p: Option<u8>,
+    /// Navigation Accuracy Category for velocity.
+    pub nac_v: Option<u8>,
+    /// Source Integrity Level.
+    pub sil: Option<u8>,
+    /// Whether `sil` is per hour or per sample.
+    pub sil_type: u8,
+    /// Geometric Vertical Accuracy.
+    pub gva: Option<u8>,
+    /// System Design Assurance.
+    pub sda: Option<u8>,
+    pub adsb_version: u8,
+    pub adsr_version: u8,
+    pub tisb_version: u8,
+}
+
+impl QualityMetrics {
+    /// Radius in meters that the true position lies within with 99.999%
+    /// integrity, or `None` if unknown.
+    pub fn containment_radius_m(&self) -> Option<f32> {
+        if self.rc == 0 { None } else { Some(self.rc as f32) }
+    }
+
+    /// The integrity and accuracy values in physical units, as defined by
+    /// the transponder's `adsb_version`.
+    ///
+    /// Version 0 transponders broadcast neither NACp nor SIL; readsb fills
+    /// those in from the NUCp, so they are not resolved here. Version 1
+    /// defines SIL without saying whether it is per hour or per sample,
+    /// which only version 2 adds through `sil_type`.
+    pub fn resolved_integrity(&self) -> Integrity {
+        let broadcast = self.adsb_version >= 1;
+
+        let position_accuracy_m = match self.nac_p.filter(|_| broadcast) {
+            Some(1) => Some(18520.0),
+            Some(2) => Some(7408.0),
+            Some(3) => Some(3704.0),
+            Some(4) => Some(1852.0),
+            Some(5) => Some(926.0),
+            Some(6) => Some(555.6),
+            Some(7) => Some(185.2),
+            Some(8) => Some(92.6),
+            Some(9) => Some(30.0),
+            Some(10) => Some(10.0),
+            Some(11..) => Some(3.0),
+            _ => None,
+        };
+
+        let sil_probability = match self.sil.filter(|_| broadcast) {
+            Some(1) => Some(1e-3),
+            Some(2) => Some(1e-5),
+            Some(3) => Some(1e-7),
+            _ => None,
+        };
+
+        let sil_per_sample = match self.sil_type {
+            1 if self.adsb_version >= 2 => Some(false),
+            2 if self.adsb_version >= 2 => Some(true),
+            _ => None,
+        };
+
+        Integrity {
+            containment_radius_m: self.containment_radius_m(),
+            position_accuracy_m,
+            sil_probability,
+            sil_per_sample,
+        }
+    }
+}
+
+/// Coarse confidence in a reported position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PositionQuality {
+    /// Containment radius above 1 NM (NIC below 5), or NACp below 5.
+    Low,
+    /// Containment radius within 1 NM (NIC 5 to 7), or NACp 5 to 7.
+    Medium,
+    /// Containment radius within 0.1 NM (NIC 8 and above) with NACp 8 or
+    /// better (EPU under 93 m) where reported.
+    High,
+}
+
+/// NIC, NACp and SIL resolved to what they promise, from
+/// [`QualityMetrics::resolved_integrity`]. Each value is `None` when unknown
+/// or not defined by the transponder's ADS-B version.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Integrity {
+    /// Radius of containment in meters, as in
+    /// [`QualityMetrics::containment_radius_m`].
+    pub containment_radius_m: Option<f32>,
+    /// Bound in meters the position is within 95% of the time (the NACp
+    /// estimated position uncertainty).
+    pub position_accuracy_m: Option<f32>,
+    /// Probability of exceeding the containment radius without an alert.
+    pub sil_probability: Option<f64>,
+    /// Whether `sil_probability` is per sample rather than per flight hour.
+    pub sil_per_sample: Option<bool>,
+}
+
+/// Wind estimated from the aircraft's heading, airspeed and ground track.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Wind {
+    /// Direction the wind blows from, degrees true in `0..360`.
+    pub direction: f32,
+    /// Speed in knots.
+    pub speed: f32,
+}
+
+/// Which reported speed a value from [`Aircraft::best_speed`] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpeedKind {
+    /// Ground speed, knots.
+    Ground,
+    /// True airspeed, knots.
+    True,
+    /// Indicated airspeed, knots.
+    Indicated,
+    /// Mach number, unitless.
+    Mach,
+}
+
+/// The altitude to show for an aircraft, from [`Aircraft::altitude`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Altitude {
+    /// On the ground, whatever altitude the transponder reports.
+    Ground,
+    /// Barometric (pressure) altitude, feet.
+    Baro(i32),
+    /// Geometric (GNSS) altitude, feet, for aircraft reporting no
+    /// barometric one.
+    Geom(i32),
+    Unknown,
+}
+
+/// How much the server has heard from an aircraft, from
+/// [`Aircraft::activity`]: globe tiles carry a rate, everything else a
+/// count.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Activity {
+    /// Messages per second, from `message_rate`.
+    Rate(f32),
+    /// Messages received in total, from `messages`.
+    Count(u16),
+    /// Neither was decoded.
+    Unknown,
+}
+
+/// Whether an aircraft is climbing, descending or holding altitude.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerticalTrend {
+    Climbing,
+    Descending,
+    Level,
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Aircraft {
+    pub hex: String,
+    pub seen_pos: Option<f32>,
+    pub seen: Option<f32>,
+    pub lon: Option<f32>,
+    pub lat: Option<f32>,
+    pub baro_rate: Option<i32>,
+    pub geom_rate: Option<i32>,
+    pub alt_baro: Option<i32>,
+    pub alt_baro_label: Option<&'static str>,
+    pub alt_geom: Option<i32>,
+    pub nav_altitude_mcp: Option<u32>,
+    pub nav_altitude_fms: Option<u32>,
+    /// Altimeter setting selected on the autopilot, in hectopascals, with a
+    /// resolution of 0.1 hPa. `None` when not reported or outside 900 to
+    /// 1100 hPa; see [`Aircraft::nav_qnh_inhg`] for inches of mercury.
+    pub nav_qnh: Option<f32>,
+    pub nav_heading: Option<f32>,
+    /// Mode A code. `None` when not reported, or when the record holds a
+    /// value with a non-octal digit, which cannot be a real code.
+    pub squawk: Option<Squawk>,
+    pub gs: Option<f32>,
+    pub mach: Option<f32>,
+    pub roll: Option<f32>,
+    pub track: Option<f32>,
+    pub track_rate: Option<f32>,
+    pub mag_heading: Option<f32>,
+    pub true_heading: Option<f32>,
+    /// Direction the wind blows from, whole degrees true. readsb derives the
+    /// wind and temperatures itself and stores them rounded but unscaled.
+    pub wd: Option<i16>,
+    /// Wind speed, whole knots.
+    pub ws: Option<i16>,
+    /// Outside air temperature, whole degrees Celsius.
+    pub oat: Option<i16>,
+    /// Total air temperature, whole degrees Celsius.
+    pub tat: Option<i16>,
+    /// True airspeed, knots.
+    pub tas: Option<u16>,
+    /// Indicated airspeed, knots.
+    pub ias: Option<u16>,
+    /// Message count, for frames that carry counts.
+    pub messages: Option<u16>,
+    /// Messages per second, for globe tiles that carry a rate instead.
+    pub message_rate: Option<f32>,
+    pub category: Option<Category>,
+    pub nav_modes: Vec<&'static str>,
+    pub emergency: Option<u8>,
+    pub signal_type: Option<SignalType>,
+    pub airground: AirGround,
+    pub nav_altitude_src: Option<u8>,
+    pub quality: QualityMetrics,
+    pub flight: Option<String>,
+    pub db_flags: u16,
+    pub tail: String,
+    pub registration: String,
+    pub receiver_count: u8,
+    pub rssi: f64,
+    /// The raw flags byte at the end of the record (`data[106]`). readsb
+    /// defines only bit 0, copied to `nogps`; the other bits are reserved
+    /// and written as zero, but are kept here as received in case a newer
+    /// server starts using them.
+    pub extra_flags: u8,
+    /// Bit 0 of `extra_flags`: readsb has stopped receiving positions the
+    /// aircraft reports from its own GPS, while still hearing it, which
+    /// usually points at GPS jamming in the area. See [`Aircraft::is_gps_lost`].
+    pub nogps: u8,
+    /// Flight status alert bit, set while the squawk has recently changed
+    /// or an emergency is declared.
+    pub alert1: Option<bool>,
+    /// Special Position Identification ("ident") bit.
+    pub spi: Option<bool>,
+    /// Id of the receiver that supplied the position, when the server
+    /// includes it (112-byte strides).
+    pub r_id: Option<String>,
+    /// The undecoded stride-sized record this aircraft was built from.
+    #[cfg(feature = "raw-bytes")]
+    pub raw: Vec<u8>,
+}
+
+impl Aircraft {
+    /// An aircraft with the given hex and nothing else known, to be filled
+    /// in with the `with_*` methods:
+    ///
+    /// ```
+    /// let aircraft = adsbexchange::Aircraft::new("a1b2c3")
+    ///     .with_position(51.47, -0.45)
+    ///     .with_alt_baro(3500)
+    ///     .with_flight("BAW123");
+    ///
+    /// assert_eq!(aircraft.flight.as_deref(), Some("BAW123"));
+    /// ```
+    pub fn new(hex: impl Into<String>) -> Self {
+        Aircraft {
+            hex: hex.into(),
+            ..Aircraft::default()
+        }
+    }
+
+    pub fn with_position(mut self, lat: f32, lon: f32) -> Self {
+        self.lat = Some(lat);
+        self.lon = Some(lon);
+        self
+    }
+
+    /// Sets the barometric altitude in feet.
+    pub fn with_alt_baro(mut self, alt_baro: i32) -> Self {
+        self.alt_baro = Some(alt_baro);
+        self
+    }
+
+    /// Sets the geometric altitude in feet.
+    pub fn with_alt_geom(mut self, alt_geom: i32) -> Self {
+        self.alt_geom = Some(alt_geom);
+        self
+    }
+
+    /// Sets ground speed in knots and track in degrees true.
+    pub fn with_velocity(mut self, gs: f32, track: f32) -> Self {
+        self.gs = Some(gs);
+        self.track = Some(track);
+        self
+    }
+
+    pub fn with_squawk(mut self, squawk: Squawk) -> Self {
+        self.squawk = Some(squawk);
+        self
+    }
+
+    pub fn with_flight(mut self, flight: impl Into<String>) -> Self {
+        self.flight = Some(flight.into());
+        self
+    }
+
+    pub fn with_registration(mut self, registration: impl Into<String>) -> Self {
+        self.registration = registration.into();
+        self
+    }
+
+    pub fn with_category(mut self, category: Category) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    pub fn with_signal_type(mut self, signal_type: SignalType) -> Self {
+        self.signal_type = Some(signal_type);
+        self
+    }
+
+    pub fn with_airground(mut self, airground: AirGround) -> Self {
+        self.airground = airground;
+        self
+    }
+
+    /// Sets how many seconds ago the aircraft was last heard from and, if
+    /// it has a position, last positioned.
+    pub fn with_seen(mut self, seen: f32) -> Self {
+        self.seen = Some(seen);
+        if self.lat.is_some() {
+            self.seen_pos = Some(seen);
+        }
+        self
+    }
+
+    /// The source record bytes, for comparing against other decoders.
+    #[cfg(feature = "raw-bytes")]
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+
+    /// Whether readsb reports the aircraft on the ground. Ground aircraft
+    /// keep the altitude the transponder reported in `alt_baro`, with
+    /// `alt_baro_label` set to `"ground"`.
+    pub fn is_on_ground(&self) -> bool {
+        self.airground == AirGround::Ground
+    }
+
+    /// Whether readsb flags the aircraft as having lost GPS (`nogps`). Its
+    /// position, if any, is then the last one received and grows stale.
+    pub fn is_gps_lost(&self) -> bool {
+        self.nogps != 0
+    }
+
+    /// Whether the pilot is squawking ident. False unless `spi` is known:
+    /// binCraft reports it only with its validity bit (`16 & data[76]`).
+    pub fn is_identing(&self) -> bool {
+        self.spi == Some(true)
+    }
+
+    /// Whether the flight status alert is raised. False unless `alert1` is
+    /// known: binCraft reports it only with its validity bit
+    /// (`8 & data[77]`).
+    pub fn is_alerting(&self) -> bool {
+        self.alert1 == Some(true)
+    }
+
+    /// Where the position came from, or `None` without a position. The
+    /// decoder drops positions whose validity bit is clear, so any position
+    /// present is one readsb considers current; this tells apart the
+    /// aircraft's own ADS-B report from an MLAT estimate or a TIS-B relay,
+    /// using `signal_type` and, for ADS-B, `quality.nic`.
+    pub fn position_source(&self) -> Option<PositionSource> {
+        self.lat?;
+        self.lon?;
+
+        let source = match self.signal_type.as_ref().map(SignalType::class) {
+            Some(SignalClass::Adsb) if self.quality.nic == 0 => PositionSource::AdsbUnverified,
+            Some(SignalClass::Adsb) => PositionSource::Adsb,
+            Some(SignalClass::Adsr) => PositionSource::Adsr,
+            Some(SignalClass::Tisb) => PositionSource::Tisb,
+            Some(SignalClass::Mlat) => PositionSource::Mlat,
+            Some(SignalClass::Other) | None => PositionSource::Other,
+        };
+
+        Some(source)
+    }
+
+    /// Barometric altitude for comparing aircraft in flight: `alt_baro`,
+    /// or `None` for aircraft on the ground.
+    pub fn airborne_altitude(&self) -> Option<i32> {
+        if self.is_on_ground() { None } else { self.alt_baro }
+    }
+
+    /// Whichever of `message_rate` and `messages` the frame carried, so
+    /// callers need not know which one a frame's version and kind fill in.
+    pub fn activity(&self) -> Activity {
+        match (self.message_rate, self.messages) {
+            (Some(rate), _) => Activity::Rate(rate),
+            (None, Some(count)) => Activity::Count(count),
+            (None, None) => Activity::Unknown,
+        }
+    }
+
+    /// The altitude to display: [`Altitude::Ground`] for aircraft on the
+    /// ground, else the barometric altitude, as ATC and tar1090 show, else
+    /// the geometric one.
+    pub fn altitude(&self) -> Altitude {
+        if self.is_on_ground() || self.alt_baro_label == Some("ground") {
+            return Altitude::Ground;
+        }
+
+        match (self.alt_baro, self.alt_geom) {
+            (Some(baro), _) => Altitude::Baro(baro),
+            (None, Some(geom)) => Altitude::Geom(geom),
+            (None, None) => Altitude::Unknown,
+        }
+    }
+
+    /// Selected altitude minus barometric altitude in feet: positive when
+    /// the crew has set a climb, negative for a descent. Uses the MCP/FCU
+    /// selection, falling back to the FMS one; `None` if either side is
+    /// missing or, for hand-built aircraft, the difference overflows.
+    pub fn altitude_delta(&self) -> Option<i32> {
+        let selected = self.nav_altitude_mcp.or(self.nav_altitude_fms)?;
+
+        i32::try_from(selected).ok()?.checked_sub(self.alt_baro?)
+    }
+
+    /// The most useful reported speed, tagged with its kind. Ground speed is
+    /// preferred, as what maps and lists show, then true airspeed,
+    /// indicated airspeed and finally Mach.
+    pub fn best_speed(&self) -> Option<(f32, SpeedKind)> {
+        self.gs.map(|gs| (gs, SpeedKind::Ground))
+            .or(self.tas.map(|tas| (tas as f32, SpeedKind::True)))
+            .or(self.ias.map(|ias| (ias as f32, SpeedKind::Indicated)))
+            .or(self.mach.map(|mach| (mach, SpeedKind::Mach)))
+    }
+
+    /// Whether the reported Mach and true airspeed agree to within 10%,
+    /// using the outside air temperature for the speed of sound, or the ISA
+    /// temperature at `alt_baro` without one. `None` if either speed, or
+    /// both temperature sources, are missing.
+    pub fn mach_tas_consistent(&self) -> Option<bool> {
+        let (mach, tas) = (self.mach? as f64, self.tas? as f64);
+
+        let kelvin = match self.oat {
+            Some(oat) => oat as f64 + 273.15,
+            None => (288.15 - 0.0019812 * self.alt_baro? as f64).max(216.65),
+        };
+
+        // Speed of sound in knots.
+        let sound = 38.967854 * kelvin.sqrt();
+
+        Some((mach * sound - tas).abs() <= 0.1 * tas.max(1.0))
+    }
+
+    /// Vertical trend, counting rates within ±64 fpm (eight of readsb's
+    /// 8 fpm steps) as level. See
+    /// [`vertical_trend_with`](Self::vertical_trend_with).
+    pub fn vertical_trend(&self) -> Option<VerticalTrend> {
+        self.vertical_trend_with(64)
+    }
+
+    /// Vertical trend from `baro_rate`, or `geom_rate` without one. Rates
+    /// within `level_fpm` of zero count as level. `None` if neither rate is
+    /// known.
+    pub fn vertical_trend_with(&self, level_fpm: i32) -> Option<VerticalTrend> {
+        let rate = self.baro_rate.or(self.geom_rate)?;
+
+        Some(if rate > level_fpm {
+            VerticalTrend::Climbing
+        } else if rate < -level_fpm {
+            VerticalTrend::Descending
+        } else {
+            VerticalTrend::Level
+        })
+    }
+
+    /// Time since the aircraft was last heard from, as of the frame time.
+    pub fn seen_duration(&self) -> Option<Duration> {
+        Duration::try_from_secs_f32(self.seen?).ok()
+    }
+
+    /// Age of the last position fix, as of the frame time.
+    pub fn seen_pos_duration(&self) -> Option<Duration> {
+        Duration::try_from_secs_f32(self.seen_pos?).ok()
+    }
+
+    /// When the last position fix was made: the frame time less `seen_pos`.
+    /// Use this rather than the frame time when choosing between positions
+    /// from different frames. `None` without a position, or when the frame
+    /// has no [`BinCraft::timestamp`].
+    pub fn position_time(&self, frame: &BinCraft) -> Option<SystemTime> {
+        frame.timestamp()?.checked_sub(self.seen_pos_duration()?)
+    }
+
+    /// The position as `(lat, lon)` in degrees, widened to `f64` for geo
+    /// math, or `None` unless both are present.
+    pub fn coordinates(&self) -> Option<(f64, f64)> {
+        Some((self.lat? as f64, self.lon? as f64))
+    }
+
+    /// Great-circle distance in nautical miles from the given point, if the
+    /// aircraft has a position.
+    pub fn distance_from(&self, lat: f64, lon: f64) -> Option<f64> {
+        let (ac_lat, ac_lon) = self.coordinates()?;
+
+        Some(geo::distance_nm(lat, lon, ac_lat, ac_lon))
+    }
+
+    /// Initial great-circle bearing in degrees true, `0..360`, from the given
+    /// observer position to the aircraft.
+    pub fn bearing_from(&self, lat: f64, lon: f64) -> Option<f64> {
+        let (ac_lat, ac_lon) = self.coordinates()?;
+
+        Some(geo::initial_bearing(lat, lon, ac_lat, ac_lon))
+    }
+
+    /// The geohash of the position, `precision` characters long, for
+    /// bucketing aircraft in stores that index by prefix. Precision is
+    /// capped at 12 characters, already finer than the position itself.
+    /// `None` without a position.
+    pub fn geohash(&self, precision: usize) -> Option<String> {
+        let (lat, lon) = self.coordinates()?;
+
+        Some(geo::geohash(lat, lon, precision))
+    }
+
+    /// The selected altimeter setting, `nav_qnh`, in inches of mercury, as
+    /// set in the US and Canada.
+    pub fn nav_qnh_inhg(&self) -> Option<f32> {
+        self.nav_qnh.map(|qnh| qnh / HPA_PER_INHG)
+    }
+
+    /// Whether moving from `(prev_lat, prev_lon)` to the current position in
+    /// `elapsed_secs` seconds is physically possible, to catch bad MLAT
+    /// solutions and other position jumps.
+    ///
+    /// The allowed speed is the reported `gs` with 50% plus 100 kt of slack,
+    /// or 1000 kt without one, and a further 0.5 NM absorbs position noise.
+    /// An aircraft without a position is never flagged.
+    pub fn is_plausible_move(&self, prev_lat: f64, prev_lon: f64, elapsed_secs: f64) -> bool {
+        let Some(distance) = self.distance_from(prev_lat, prev_lon) else {
+            return true;
+        };
+
+        let speed_kt = match self.gs {
+            Some(gs) => gs as f64 * 1.5 + 100.0,
+            None => MAX_PLAUSIBLE_SPEED_KT,
+        };
+
+        distance <= speed_kt / 3600.0 * elapsed_secs.max(0.0) + POSITION_TOLERANCE_NM
+    }
+
+    /// Confidence in the current position, from the containment radius (or
+    /// NIC when `rc` is unknown) capped by the NACp accuracy category.
+    ///
+    /// readsb folds `nic_a`/`nic_c` into `nic` and `rc` already, so they are
+    /// not consulted separately. Returns `None` without a position or when
+    /// neither `rc` nor `nic` is known.
+    pub fn position_quality(&self) -> Option<PositionQuality> {
+        self.lat?;
+        self.lon?;
+
+        let quality = &self.quality;
+
+        let integrity = match quality.containment_radius_m() {
+            Some(rc) if rc <= 185.2 => PositionQuality::High,
+            Some(rc) if rc <= 1852.0 => PositionQuality::Medium,
+            Some(_) => PositionQuality::Low,
+            None => match quality.nic {
+                0 => return None,
+                8.. => PositionQuality::High,
+                5..=7 => PositionQuality::Medium,
+                _ => PositionQuality::Low,
+            },
+        };
+
+        let accuracy = match quality.nac_p {
+            Some(8..) | None => PositionQuality::High,
+            Some(5..=7) => PositionQuality::Medium,
+            Some(_) => PositionQuality::Low,
+        };
+
+        Some(integrity.min(accuracy))
+    }
+
+    /// Whether the aircraft declares an emergency, through its emergency
+    /// status or by squawking 7500, 7600 or 7700.
+    pub fn is_emergency(&self) -> bool {
+        let squawk = self.squawk.map(|squawk| squawk.raw());
+
+        self.emergency.is_some_and(|emergency| emergency != 0)
+            || matches!(squawk, Some(0x7500 | 0x7600 | 0x7700))
+    }
+
+    /// The address in `hex` as a key for sets and maps, or `None` if `hex`
+    /// is not a valid address, which only happens for hand-built aircraft.
+    pub fn icao(&self) -> Option<Icao> {
+        self.hex.parse().ok()
+    }
+
+    /// A label for the squawk when it is a well-known special code; see
+    /// [`Squawk::meaning`].
+    pub fn squawk_meaning(&self) -> Option<&'static str> {
+        self.squawk?.meaning()
+    }
+
+    /// The reported wind, present only when both direction and speed are
+    /// valid.
+    pub fn wind(&self) -> Option<Wind> {
+        let speed = self.ws?;
+
+        if speed < 0 {
+            return None;
+        }
+
+        Some(Wind {
+            direction: (self.wd? as f32).rem_euclid(360.0),
+            speed: speed as f32,
+        })
+    }
+
+    /// How far, in knots, the reported wind is from closing the triangle of
+    /// air and ground velocity: the length of ground velocity (`gs` along
+    /// `track`) minus air velocity (`tas` along `true_heading`) minus the
+    /// wind vector from [`Aircraft::wind`]. Zero for perfectly consistent
+    /// data. `None` unless all six values are present.
+    ///
+    /// readsb derives the wind from these same fields, so a few knots come
+    /// from rounding and from the fields being sampled at different times; a
+    /// large residual points at a bad heading or airspeed.
+    pub fn wind_triangle_residual(&self) -> Option<f32> {
+        let wind = self.wind()?;
+
+        let vector = |speed: f32, direction: f32| {
+            let direction = direction.to_radians();
+            (speed * direction.sin(), speed * direction.cos())
+        };
+
+        let ground = vector(self.gs?, self.track?);
+        let air = vector(self.tas? as f32, self.true_heading?);
+        // The wind blows towards the opposite of where it comes from.
+        let wind = vector(wind.speed, wind.direction + 180.0);
+
+        let east = ground.0 - air.0 - wind.0;
+        let north = ground.1 - air.1 - wind.1;
+
+        Some(east.hypot(north))
+    }
+
+    /// Dead-reckoned position `seconds_ahead` seconds after the frame time,
+    /// advancing the last fix along `track` at `gs`.
+    ///
+    /// The last fix is `seen_pos` seconds old, so the projection covers
+    /// `seen_pos + seconds_ahead` seconds of travel. Returns `None` if the
+    /// position, track or ground speed is missing.
+    pub fn projected_position(&self, seconds_ahead: f64) -> Option<(f64, f64)> {
+        let (lat, lon) = self.coordinates()?;
+        let track = self.track? as f64;
+        let gs = self.gs? as f64;
+
+        let elapsed = self.seen_pos.unwrap_or(0.0) as f64 + seconds_ahead;
+
+        Some(geo::destination(lat, lon, track, gs / 3600.0 * elapsed))
+    }
+}
+
+/// Altitudes from here up are written as flight levels.
+const FLIGHT_LEVEL_FROM_FT: i32 = 18_000;
+
+impl fmt::Display for Aircraft {
+    /// One line for logs, such as `DLH123 (3c6545) FL350 450kt 270° 7000`:
+    /// callsign and hex, then whichever of altitude, ground speed, track and
+    /// squawk are known. Altitudes below 18,000 ft are given in feet, and
+    /// aircraft on the ground as `ground`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.flight {
+            Some(flight) => write!(f, "{} ({})", flight, self.hex)?,
+            None => write!(f, "{}", self.hex)?,
+        }
+
+        if self.is_on_ground() {
+            write!(f, " ground")?;
+        } else if let Some(alt) = self.alt_baro {
+            if alt >= FLIGHT_LEVEL_FROM_FT {
+                write!(f, " FL{:03}", (alt + 50) / 100)?;
+            } else {
+                write!(f, " {}ft", alt)?;
+            }
+        }
+
+        if let Some(gs) = self.gs {
+            write!(f, " {:.0}kt", gs)?;
+        }
+
+        if let Some(track) = self.track {
+            write!(f, " {:03}°", (track.round() as i32).rem_euclid(360))?;
+        }
+
+        if let Some(squawk) = self.squawk {
+            write!(f, " {}", squawk)?;
+        }
+
+        Ok(())
+    }
+}
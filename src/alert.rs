@@ -0,0 +1,56 @@
+use crate::aircraft::Aircraft;
+use crate::bincraft::BinCraft;
+
+/// Emergency status values, as readsb numbers them.
+const EMERGENCY_NORDO: u8 = 4;
+
+/// Aircraft needing attention, from [`BinCraft::alert_tiers`], grouped from
+/// most to least urgent. Each aircraft appears only in the most urgent tier
+/// it qualifies for, in frame order within the tier.
+#[derive(Debug, Default)]
+pub struct AlertTiers<'a> {
+    /// Squawking 7700 or declaring a general, medical, minimum fuel,
+    /// unlawful interference or downed emergency.
+    pub critical: Vec<&'a Aircraft>,
+    /// Squawking 7600 or declaring a loss of communications.
+    pub comms_loss: Vec<&'a Aircraft>,
+    /// Squawking 7500 without declaring an emergency; a declared unlawful
+    /// interference is critical.
+    pub hijack: Vec<&'a Aircraft>,
+    /// Raising the flight status alert or squawking ident, with no
+    /// emergency declared.
+    pub flagged: Vec<&'a Aircraft>,
+}
+
+impl AlertTiers<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.critical.is_empty() && self.comms_loss.is_empty() && self.hijack.is_empty() && self.flagged.is_empty()
+    }
+}
+
+impl BinCraft {
+    /// Sorts the aircraft needing attention into [`AlertTiers`], going by the
+    /// squawk and emergency status, then the alert and ident bits. Reserved
+    /// emergency values are treated as critical.
+    pub fn alert_tiers(&self) -> AlertTiers<'_> {
+        let mut tiers = AlertTiers::default();
+
+        for aircraft in &self.aircraft {
+            let squawk = aircraft.squawk.map(|squawk| squawk.raw());
+            let emergency = aircraft.emergency.filter(|&emergency| emergency != 0);
+
+            let tier = match (squawk, emergency) {
+                (Some(0x7700), _) => &mut tiers.critical,
+                (_, Some(emergency)) if emergency != EMERGENCY_NORDO => &mut tiers.critical,
+                (Some(0x7600), _) | (_, Some(EMERGENCY_NORDO)) => &mut tiers.comms_loss,
+                (Some(0x7500), _) => &mut tiers.hijack,
+                _ if aircraft.is_alerting() || aircraft.is_identing() => &mut tiers.flagged,
+                _ => continue,
+            };
+
+            tier.push(aircraft);
+        }
+
+        tiers
+    }
+}
@@ -0,0 +1,1013 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytemuck::{Pod, Zeroable};
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::aircraft::{AirGround, Aircraft, SignalClass, SignalType, PLAUSIBLE_QNH_HPA};
+use crate::category::Category;
+use crate::scale::{
+    ALT_STEP, GS_SCALE, HEADING_SCALE, MACH_SCALE, MESSAGE_RATE_SCALE, NAV_ALT_STEP, POSITION_SCALE, QNH_SCALE,
+    ROLL_SCALE, SEEN_SCALE, VERTICAL_RATE_STEP,
+};
+use crate::error::Error;
+use crate::fields::FieldGroups;
+use crate::geo::{self, BoundingBox};
+use crate::squawk::Squawk;
+use crate::warning::Warning;
+
+#[derive(Clone, Debug)]
+pub struct BinCraft {
+    pub now: f64,
+    pub stride: u32,
+    /// Number of aircraft with a position known to the server across the
+    /// whole world, not just those in this frame. Use
+    /// [`BinCraft::positioned`] for the aircraft in this frame.
+    pub global_ac_count_withpos: u32,
+    /// The globe tile this frame covers, for frames served as globe tiles,
+    /// or `0` otherwise. Map it to an area with [`GlobeIndex`](crate::GlobeIndex).
+    pub globe_index: u32,
+    pub south: i16,
+    pub west: i16,
+    pub north: i16,
+    pub east: i16,
+    pub messages: u32,
+    /// Position of the server's receiver in degrees, `0.0` when it has
+    /// none configured, such as on aggregators.
+    pub receiver_lat: f64,
+    pub receiver_lon: f64,
+    /// Format version the server wrote, a date such as `20240218`, or `0`
+    /// from servers predating the field. It decides how some fields are
+    /// encoded, such as whether globe tiles carry a message rate.
+    pub bin_craft_version: u32,
+    /// When the frame was received, recorded by the fetch functions and
+    /// `None` for frames parsed from files or bytes. See
+    /// [`BinCraft::fetch_latency`].
+    pub fetched_at: Option<SystemTime>,
+    /// Anything unexpected the fetch functions noticed about the response,
+    /// such as a [`Warning::BoxClamped`](crate::Warning::BoxClamped).
+    /// Empty for frames parsed from files or bytes.
+    pub warnings: Vec<Warning>,
+    pub aircraft: Vec<Aircraft>,
+}
+
+/// Altitudes [`BinCraft::trim_altitudes`] keeps, in feet: from below the
+/// lowest airfields to above anything but a few military types.
+const PLAUSIBLE_ALTITUDE_FT: RangeInclusive<i32> = -2_000..=60_000;
+
+/// Orders present values before missing ones, keeping the sort stable.
+pub(crate) fn cmp_present<T>(a: Option<T>, b: Option<T>, cmp: impl Fn(&T, &T) -> Ordering) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => cmp(&a, &b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+impl BinCraft {
+    /// The frame time, `now`, as a [`SystemTime`], or `None` when `now` is
+    /// negative, NaN, or too large for one, as it can be when set by hand.
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        UNIX_EPOCH.checked_add(Duration::try_from_secs_f64(self.now).ok()?)
+    }
+
+    /// How long ago, by the local clock, the server took this snapshot:
+    /// for a freshly fetched frame the end-to-end latency of the feed,
+    /// growing as the frame is held. Zero if the local clock is behind the
+    /// server's; `None` without a [`BinCraft::timestamp`].
+    pub fn age(&self) -> Option<Duration> {
+        Some(SystemTime::now().duration_since(self.timestamp()?).unwrap_or_default())
+    }
+
+    /// How long after the server's snapshot the frame was received, from
+    /// `fetched_at`, for alerting when a feed falls behind. `None` for
+    /// frames that were not fetched or have no [`BinCraft::timestamp`];
+    /// zero if the local clock is behind the server's.
+    pub fn fetch_latency(&self) -> Option<Duration> {
+        Some(self.fetched_at?.duration_since(self.timestamp()?).unwrap_or_default())
+    }
+
+    /// Aircraft in this frame that have a position. Unlike
+    /// `global_ac_count_withpos` this only covers the requested area.
+    pub fn positioned(&self) -> impl Iterator<Item = &Aircraft> {
+        self.aircraft
+            .iter()
+            .filter(|a| a.lat.is_some() && a.lon.is_some())
+    }
+
+    /// The smallest box containing every positioned aircraft in this frame,
+    /// or `None` if none has a position.
+    ///
+    /// Longitudes are compared as plain numbers, so coverage spanning the
+    /// antimeridian comes back as a box covering the rest of the world.
+    pub fn coverage_bounds(&self) -> Option<BoundingBox> {
+        self.positioned()
+            .filter_map(Aircraft::coordinates)
+            .fold(None, |bounds: Option<BoundingBox>, (lat, lon)| match bounds {
+                Some(b) => Some(BoundingBox::new(b.south.min(lat), b.north.max(lat), b.west.min(lon), b.east.max(lon))),
+                None => Some(BoundingBox::new(lat, lat, lon, lon)),
+            })
+    }
+
+    /// Aircraft whose signal type matches `pred`. Aircraft without a signal
+    /// type are excluded.
+    pub fn by_signal<F: Fn(&SignalType) -> bool>(&self, pred: F) -> Vec<&Aircraft> {
+        self.aircraft
+            .iter()
+            .filter(|a| a.signal_type.as_ref().is_some_and(&pred))
+            .collect()
+    }
+
+    /// Aircraft whose emitter category matches `pred`. Aircraft without a
+    /// category are excluded.
+    pub fn by_category_where<F: Fn(&Category) -> bool>(&self, pred: F) -> Vec<&Aircraft> {
+        self.aircraft
+            .iter()
+            .filter(|a| a.category.as_ref().is_some_and(&pred))
+            .collect()
+    }
+
+    /// Aircraft of the given emitter category, e.g. [`Category::Rotorcraft`].
+    pub fn by_category(&self, category: Category) -> Vec<&Aircraft> {
+        self.by_category_where(|c| *c == category)
+    }
+
+    /// Aircraft received directly via ADS-B.
+    pub fn adsb_only(&self) -> Vec<&Aircraft> {
+        self.by_signal(SignalType::is_adsb)
+    }
+
+    /// Aircraft rebroadcast via ADS-R.
+    pub fn adsr_only(&self) -> Vec<&Aircraft> {
+        self.by_signal(SignalType::is_adsr)
+    }
+
+    /// Aircraft relayed via TIS-B.
+    pub fn tisb_only(&self) -> Vec<&Aircraft> {
+        self.by_signal(SignalType::is_tisb)
+    }
+
+    /// Aircraft positioned by multilateration.
+    pub fn mlat_only(&self) -> Vec<&Aircraft> {
+        self.by_signal(|signal_type| matches!(signal_type, SignalType::Mlat))
+    }
+
+    /// Every aircraft except ADS-R and TIS-B rebroadcasts of a hex that is
+    /// also received directly via ADS-B, the duplicates tar1090 hides.
+    pub fn primary_only(&self) -> Vec<&Aircraft> {
+        let class = |a: &Aircraft| a.signal_type.as_ref().map(SignalType::class);
+
+        let direct = self.aircraft
+            .iter()
+            .filter(|a| class(a) == Some(SignalClass::Adsb))
+            .map(|a| a.hex.as_str())
+            .collect::<HashSet<_>>();
+
+        self.aircraft
+            .iter()
+            .filter(|a| {
+                !matches!(class(a), Some(SignalClass::Adsr | SignalClass::Tisb)) || !direct.contains(a.hex.as_str())
+            })
+            .collect()
+    }
+
+    /// Airborne aircraft with a ground speed of at least `min_gs` knots.
+    /// Aircraft on the ground or without a ground speed are excluded.
+    pub fn moving(&self, min_gs: f32) -> Vec<&Aircraft> {
+        self.aircraft
+            .iter()
+            .filter(|a| !a.is_on_ground() && a.gs.is_some_and(|gs| gs >= min_gs))
+            .collect()
+    }
+
+    /// Aircraft heard from within the last `max_age_secs` seconds. Aircraft
+    /// without a `seen` value are dropped.
+    pub fn fresh(&self, max_age_secs: f32) -> Vec<&Aircraft> {
+        self.aircraft
+            .iter()
+            .filter(|a| a.seen.is_some_and(|seen| seen <= max_age_secs))
+            .collect()
+    }
+
+    /// Aircraft whose position was updated within the last `max_age_secs`
+    /// seconds. Aircraft without a position are dropped.
+    pub fn fresh_positions(&self, max_age_secs: f32) -> Vec<&Aircraft> {
+        self.aircraft
+            .iter()
+            .filter(|a| a.seen_pos.is_some_and(|seen_pos| seen_pos <= max_age_secs))
+            .collect()
+    }
+
+    /// Positioned aircraft whose reported integrity and accuracy meet
+    /// `min_nic` and `min_nac_p`, for analysis that needs trustworthy
+    /// positions.
+    ///
+    /// An aircraft is kept only if it has a position, a NIC of at least
+    /// `min_nic` with a known containment radius (`rc`), a reported NACp of
+    /// at least `min_nac_p`, and a reported non-zero SIL. A missing metric
+    /// never passes, so MLAT and most TIS-B positions, which carry none, are
+    /// dropped; so are positions with SIL 0, which promise no integrity at
+    /// all.
+    pub fn high_quality(&self, min_nic: u8, min_nac_p: u8) -> Vec<&Aircraft> {
+        self.positioned()
+            .filter(|a| {
+                let quality = &a.quality;
+
+                quality.nic >= min_nic
+                    && quality.containment_radius_m().is_some()
+                    && quality.nac_p.is_some_and(|nac_p| nac_p >= min_nac_p)
+                    && quality.sil.is_some_and(|sil| sil > 0)
+            })
+            .collect()
+    }
+
+    /// Sorts aircraft nearest-first from the given point. Aircraft without a
+    /// position are moved to the end in their original order.
+    pub fn sort_by_distance(&mut self, lat: f64, lon: f64) {
+        self.aircraft.sort_by(|a, b| {
+            cmp_present(a.distance_from(lat, lon), b.distance_from(lat, lon), f64::total_cmp)
+        });
+    }
+
+    /// Sorts aircraft by barometric altitude, lowest first. Aircraft on the
+    /// ground or without an altitude are moved to the end in their original
+    /// order; see [`Aircraft::airborne_altitude`].
+    pub fn sort_by_altitude(&mut self) {
+        self.aircraft.sort_by(|a, b| cmp_present(a.airborne_altitude(), b.airborne_altitude(), Ord::cmp));
+    }
+
+    /// Sorts aircraft by time since the last message, most recent first.
+    /// Aircraft without a `seen` value are moved to the end in their original
+    /// order.
+    pub fn sort_by_seen(&mut self) {
+        self.aircraft.sort_by(|a, b| cmp_present(a.seen, b.seen, f32::total_cmp));
+    }
+
+    /// Sorts aircraft by hex address, for an order that does not depend on
+    /// the server or on how frames were merged. Non-ICAO addresses, written
+    /// with a leading `~`, come after every ICAO one; aircraft sharing a hex
+    /// keep their original order.
+    pub fn sort_by_hex(&mut self) {
+        self.aircraft.sort_by(|a, b| a.hex.cmp(&b.hex));
+    }
+
+    /// Clears barometric and geometric altitudes outside -2,000 to 60,000
+    /// ft, which come from corrupt records rather than aircraft: readsb
+    /// stores altitude in 25 ft steps, so a few flipped bits land tens of
+    /// thousands of feet away. Returns how many values were cleared.
+    pub fn trim_altitudes(&mut self) -> usize {
+        self.trim_altitudes_with(PLAUSIBLE_ALTITUDE_FT)
+    }
+
+    /// Like [`BinCraft::trim_altitudes`], keeping altitudes within `range_ft`
+    /// feet, bounds included.
+    pub fn trim_altitudes_with(&mut self, range_ft: RangeInclusive<i32>) -> usize {
+        let mut trimmed = 0;
+
+        for aircraft in &mut self.aircraft {
+            for altitude in [&mut aircraft.alt_baro, &mut aircraft.alt_geom] {
+                if altitude.is_some_and(|feet| !range_ft.contains(&feet)) {
+                    *altitude = None;
+                    trimmed += 1;
+                }
+            }
+        }
+
+        trimmed
+    }
+
+    /// Returns up to `n` positioned aircraft closest to the given point,
+    /// nearest first.
+    pub fn nearest(&self, lat: f64, lon: f64, n: usize) -> Vec<&Aircraft> {
+        let mut positioned = self.aircraft
+            .iter()
+            .filter_map(|a| a.distance_from(lat, lon).map(|d| (d, a)))
+            .collect::<Vec<_>>();
+
+        positioned.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        positioned
+            .into_iter()
+            .take(n)
+            .map(|(_, a)| a)
+            .collect()
+    }
+
+    /// How many aircraft squawk each code, keyed by the code as written,
+    /// such as `"7000"`. Aircraft without a squawk are not counted.
+    pub fn squawk_histogram(&self) -> HashMap<String, usize> {
+        let mut histogram = HashMap::new();
+
+        for squawk in self.aircraft.iter().filter_map(|a| a.squawk) {
+            *histogram.entry(squawk.to_string()).or_insert(0) += 1;
+        }
+
+        histogram
+    }
+
+    /// How many aircraft each signal type reports, for showing the mix of
+    /// ADS-B, MLAT and TIS-B in a feed; see [`SignalType::class`] for
+    /// broader groups. Aircraft without a signal type are not counted.
+    pub fn signal_type_counts(&self) -> HashMap<SignalType, usize> {
+        let mut counts = HashMap::new();
+
+        for signal_type in self.aircraft.iter().filter_map(|a| a.signal_type) {
+            *counts.entry(signal_type).or_insert(0) += 1;
+        }
+
+        counts
+    }
+
+    /// Callsigns reported by more than one hex, each with the aircraft
+    /// reporting it, in order of first appearance. Callsigns are compared as
+    /// decoded, trimmed of padding and upper-cased. The same hex appearing
+    /// twice, as an ADS-B report and its TIS-B shadow, does not count as
+    /// reuse on its own, but both entries are listed when another hex shares
+    /// the callsign.
+    pub fn duplicate_callsigns(&self) -> Vec<(String, Vec<&Aircraft>)> {
+        let mut groups = Vec::<(&str, Vec<&Aircraft>)>::new();
+        let mut index = HashMap::new();
+
+        for aircraft in &self.aircraft {
+            let Some(flight) = aircraft.flight.as_deref() else {
+                continue;
+            };
+
+            let group = *index.entry(flight).or_insert_with(|| {
+                groups.push((flight, Vec::new()));
+                groups.len() - 1
+            });
+
+            groups[group].1.push(aircraft);
+        }
+
+        groups
+            .into_iter()
+            .filter(|(_, aircraft)| aircraft.iter().any(|a| a.hex != aircraft[0].hex))
+            .map(|(flight, aircraft)| (flight.to_string(), aircraft))
+            .collect()
+    }
+
+    /// Number of aircraft declaring an emergency; see
+    /// [`Aircraft::is_emergency`].
+    pub fn emergency_count(&self) -> usize {
+        self.aircraft.iter().filter(|a| a.is_emergency()).count()
+    }
+
+    /// Every pair of airborne aircraft within `horiz_nm` great-circle
+    /// distance and `vert_ft` barometric altitude of each other, such as for
+    /// conflict displays. Each pair is listed once, in frame order, and
+    /// aircraft without a position or an airborne altitude are skipped.
+    ///
+    /// Aircraft are swept in latitude order so that only those within
+    /// `horiz_nm` worth of latitude are compared, which keeps this close to
+    /// linear for feeds spread over a large area; a dense cluster is still
+    /// compared pairwise.
+    pub fn close_pairs(&self, horiz_nm: f64, vert_ft: i32) -> Vec<(&Aircraft, &Aircraft)> {
+        let mut candidates = self.aircraft
+            .iter()
+            .enumerate()
+            .filter_map(|(i, a)| {
+                let (lat, lon) = a.coordinates()?;
+                Some((i, lat, lon, a.airborne_altitude()?))
+            })
+            .collect::<Vec<_>>();
+
+        candidates.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        // A degree of latitude is just over 60 NM, so this window never
+        // misses a pair.
+        let window = horiz_nm / 60.0;
+
+        let mut pairs = Vec::new();
+
+        for (n, &(i, lat, lon, alt)) in candidates.iter().enumerate() {
+            for &(j, other_lat, other_lon, other_alt) in &candidates[n + 1..] {
+                if other_lat - lat > window {
+                    break;
+                }
+
+                if (alt - other_alt).abs() <= vert_ft && geo::distance_nm(lat, lon, other_lat, other_lon) <= horiz_nm {
+                    pairs.push((i.min(j), i.max(j)));
+                }
+            }
+        }
+
+        pairs.sort_unstable();
+
+        pairs
+            .into_iter()
+            .map(|(i, j)| (&self.aircraft[i], &self.aircraft[j]))
+            .collect()
+    }
+}
+
+/// Header fields that change how individual records are decoded.
+///
+/// The record layout has only ever grown at the end, so older captures
+/// differ from current ones in two ways, both handled here:
+///
+/// - Shorter strides end before the trailing fields. `tail` needs 92
+///   bytes, `registration` 104, `receiver_count` and `rssi` 106,
+///   `extra_flags` 107 and `r_id` 112; fields a record does not reach are
+///   left empty.
+/// - Before version 20220916, globe tiles carried a message count in the
+///   slot that now holds the message rate; see `use_message_rate`.
+///
+/// Every other offset is the same in every version tar1090 decodes.
+///
+/// Made from a parsed header by [`BinCraftHeader::context`], for decoding
+/// records one at a time with [`decode_record`].
+#[derive(Clone, Copy, Debug)]
+pub struct ParseContext {
+    pub(crate) stride: u32,
+    pub(crate) globe_index: u32,
+    pub(crate) bin_craft_version: u32,
+    pub(crate) groups: FieldGroups,
+}
+
+impl ParseContext {
+    /// Length of each record, and of the header before the first one.
+    pub fn stride(&self) -> u32 {
+        self.stride
+    }
+
+    /// Globe tiles from version 20220916 on carry a message rate in place of
+    /// the message count.
+    fn use_message_rate(&self) -> bool {
+        self.globe_index != 0 && self.bin_craft_version >= 20220916
+    }
+}
+
+/// A decoded float, or `None` if it is NaN or infinite, which JSON cannot
+/// represent. The scaled integers in a record always give finite values;
+/// this keeps it that way whatever the field.
+fn finite(value: f32) -> Option<f32> {
+    Some(value).filter(|value| value.is_finite())
+}
+
+#[inline]
+pub(crate) fn build_aircraft(
+    data: &[u8],
+    ctx: &ParseContext,
+) -> Aircraft {
+    let mut aircraft = Aircraft::default();
+
+    #[cfg(feature = "raw-bytes")]
+    {
+        aircraft.raw = data.to_vec();
+    }
+
+    // Fields are read on demand rather than decoding the whole record into
+    // u32/s32/u16/s16 arrays up front; indices match tar1090's typed views.
+    let u32 = |i: usize| LittleEndian::read_u32(&data[i * 4..i * 4 + 4]);
+    let s32 = |i: usize| LittleEndian::read_i32(&data[i * 4..i * 4 + 4]);
+    let u16 = |i: usize| LittleEndian::read_u16(&data[i * 2..i * 2 + 2]);
+    let s16 = |i: usize| LittleEndian::read_i16(&data[i * 2..i * 2 + 2]);
+
+    let stride = ctx.stride as usize;
+
+    let t = s32(0) & (1 << 24);
+
+    aircraft.hex = format!("{:06x}", 16777215 & s32(0));
+    aircraft.hex = if t != 0 { "~".to_string() + &aircraft.hex } else { aircraft.hex };
+
+    // readsb writes i32::MAX as the latitude of an aircraft with no
+    // position; leave the position unset rather than decoding it.
+    let position_unset = s32(3) == i32::MAX;
+
+    aircraft.seen = finite(u16(3) as f32 / SEEN_SCALE);
+
+    if !position_unset {
+        aircraft.seen_pos = finite(u16(2) as f32 / SEEN_SCALE);
+        // Divide in f64 so the result is the f32 nearest the true value,
+        // which also lets the writer recover the original microdegrees.
+        aircraft.lon = Some((s32(2) as f64 / POSITION_SCALE) as f32);
+        aircraft.lat = Some((s32(3) as f64 / POSITION_SCALE) as f32);
+    }
+
+    aircraft.alt_baro = Some(ALT_STEP * s16(10) as i32);
+    aircraft.alt_geom = Some(ALT_STEP * s16(11) as i32);
+    aircraft.airground = AirGround::from(15 & data[68]);
+
+    // Older servers wrote shorter records that end before some of the
+    // trailing fields; those are left empty.
+    if stride >= 107 {
+        aircraft.extra_flags = data[106];
+    }
+
+    aircraft.nogps = 1 & aircraft.extra_flags;
+
+    let groups = ctx.groups;
+
+    if groups.contains(FieldGroups::VELOCITY) {
+        aircraft.baro_rate = Some(VERTICAL_RATE_STEP * s16(8) as i32);
+        aircraft.geom_rate = Some(VERTICAL_RATE_STEP * s16(9) as i32);
+        aircraft.gs = finite(s16(17) as f32 / GS_SCALE);
+        aircraft.mach = finite(s16(18) as f32 / MACH_SCALE);
+        aircraft.roll = finite(s16(19) as f32 / ROLL_SCALE);
+        aircraft.track = finite(s16(20) as f32 / HEADING_SCALE);
+        aircraft.track_rate = finite(s16(21) as f32 / ROLL_SCALE);
+        aircraft.mag_heading = finite(s16(22) as f32 / HEADING_SCALE);
+        aircraft.true_heading = finite(s16(23) as f32 / HEADING_SCALE);
+        aircraft.wd = Some(s16(24));
+        aircraft.ws = Some(s16(25));
+        aircraft.oat = Some(s16(26));
+        aircraft.tat = Some(s16(27));
+        aircraft.tas = Some(u16(28));
+        aircraft.ias = Some(u16(29));
+    }
+
+    if groups.contains(FieldGroups::NAV) {
+        aircraft.nav_altitude_mcp = Some(NAV_ALT_STEP * u16(12) as u32);
+        aircraft.nav_altitude_fms = Some(NAV_ALT_STEP * u16(13) as u32);
+        aircraft.nav_qnh = finite(s16(14) as f32 / QNH_SCALE).filter(|qnh| PLAUSIBLE_QNH_HPA.contains(qnh));
+        aircraft.nav_heading = finite(s16(15) as f32 / HEADING_SCALE);
+        aircraft.nav_altitude_src = Some((240 & data[68]) >> 4);
+    }
+
+    if groups.contains(FieldGroups::QUALITY) {
+        let quality = &mut aircraft.quality;
+
+        quality.rc = u16(30);
+        quality.nic = data[65];
+        quality.sil_type = 15 & data[69];
+        quality.adsb_version = (240 & data[69]) >> 4;
+        quality.adsr_version = 15 & data[70];
+        quality.tisb_version = (240 & data[70]) >> 4;
+        quality.nac_p = Some(15 & data[71]);
+        quality.nac_v = Some((240 & data[71]) >> 4);
+        quality.sil = Some(3 & data[72]);
+        quality.gva = Some((12 & data[72]) >> 2);
+        quality.sda = Some((48 & data[72]) >> 4);
+        quality.nic_a = Some((64 & data[72]) >> 6);
+        quality.nic_c = Some((128 & data[72]) >> 7);
+    }
+
+    if groups.contains(FieldGroups::META) {
+        // readsb has already rearranged the reply's A1A2A4 B1B2B4 C1C2C4
+        // D1D2D4 bits into one digit per nibble; a non-octal nibble means
+        // a corrupt record rather than a code.
+        aircraft.squawk = Some(Squawk::from_raw(u16(16))).filter(Squawk::is_valid);
+
+        if ctx.use_message_rate() {
+            aircraft.message_rate = finite(u16(31) as f32 / MESSAGE_RATE_SCALE);
+        } else {
+            aircraft.messages = Some(u16(31));
+        }
+
+        aircraft.category = if data[64] != 0 { Some(Category::from_raw(data[64])) } else { None };
+        aircraft.emergency = Some(15 & data[67]);
+
+        // Callsigns are space-padded to eight characters, with NUL padding
+        // after.
+        let flight =
+            String::from_utf8_lossy(&data[78..86])
+                .trim_matches(|c: char| c == char::from(0) || c.is_whitespace())
+                .to_ascii_uppercase();
+
+        aircraft.flight = if flight.is_empty() { None } else { Some(flight) };
+
+        aircraft.db_flags = u16(43);
+
+        if stride >= 92 {
+            aircraft.tail =
+                String::from_utf8_lossy(&data[88..92])
+                    .trim_end_matches(char::from(0))
+                    .to_string();
+        }
+
+        if stride >= 104 {
+            aircraft.registration =
+                String::from_utf8_lossy(&data[92..104])
+                    .trim_end_matches(char::from(0))
+                    .to_string();
+        }
+
+        if stride >= 106 {
+            aircraft.receiver_count = data[104];
+            // The offset keeps a zero reading finite, at -49.5 dBFS, as in
+            // tar1090.
+            aircraft.rssi = 10.0 * (data[105] as f64 * data[105] as f64 / 65025.0 + 1125e-8).log10();
+        }
+
+        // 112-byte strides carry the id of the receiver that supplied the
+        // position, rendered the same way tar1090 shows it.
+        if stride >= 112 {
+            let r_id = (data[107], u32(27));
+
+            if r_id != (0, 0) {
+                aircraft.r_id = Some(format!("{:02x}-{:04x}-{:04x}", r_id.0, r_id.1 >> 16, r_id.1 & 0xffff));
+            }
+        }
+    }
+
+    // Copy the record only in the rare case the validity bits need patching.
+    let mut data = Cow::Borrowed(data);
+
+    if aircraft.nogps != 0 && position_unset {
+        data.to_mut()[73] |= 64;
+        data.to_mut()[73] |= 16;
+    }
+
+    if groups.contains(FieldGroups::QUALITY) {
+        aircraft.quality.nic_baro = Some(1 & data[73] != 0);
+    }
+
+    if groups.contains(FieldGroups::META) {
+        aircraft.alert1 = Some(2 & data[73] != 0);
+        aircraft.spi = Some(4 & data[73] != 0);
+    }
+
+    if 8 & data[73] == 0 { aircraft.flight = None; }
+    if 16 & data[73] == 0 { aircraft.alt_baro = None; }
+    if 32 & data[73] == 0 { aircraft.alt_geom = None; }
+    if 64 & data[73] == 0 {
+        aircraft.lat = None;
+        aircraft.lon = None;
+        aircraft.seen_pos = None;
+    }
+    if 128 & data[73] == 0 { aircraft.gs = None; }
+
+    if 1 & data[74] == 0 { aircraft.ias = None; }
+    if 2 & data[74] == 0 { aircraft.tas = None; }
+    if 4 & data[74] == 0 { aircraft.mach = None; }
+    if 8 & data[74] == 0 {
+        aircraft.track = None;
+        //aircraft.calc_track = None;
+    }
+    if 16 & data[74] == 0 { aircraft.track_rate = None; }
+    if 32 & data[74] == 0 { aircraft.roll = None; }
+    if 64 & data[74] == 0 { aircraft.mag_heading = None; }
+    if 128 & data[74] == 0 { aircraft.true_heading = None; }
+
+    if 1 & data[75] == 0 { aircraft.baro_rate = None; }
+    if 2 & data[75] == 0 { aircraft.geom_rate = None; }
+
+    if 4 & data[75] == 0 { aircraft.quality.nic_a = None; }
+    if 8 & data[75] == 0 { aircraft.quality.nic_c = None; }
+    if 16 & data[75] == 0 { aircraft.quality.nic_baro = None; }
+    if 32 & data[75] == 0 { aircraft.quality.nac_p = None; }
+    if 64 & data[75] == 0 { aircraft.quality.nac_v = None; }
+    if 128 & data[75] == 0 { aircraft.quality.sil = None; }
+
+    if 1 & data[76] == 0 { aircraft.quality.gva = None; }
+    if 2 & data[76] == 0 { aircraft.quality.sda = None; }
+    if 4 & data[76] == 0 { aircraft.squawk = None; }
+    if 8 & data[76] == 0 { aircraft.emergency = None; }
+    if 16 & data[76] == 0 { aircraft.spi = None; }
+    if 32 & data[76] == 0 { aircraft.nav_qnh = None; }
+    if 64 & data[76] == 0 { aircraft.nav_altitude_mcp = None; }
+    if 128 & data[76] == 0 { aircraft.nav_altitude_fms = None; }
+
+    if 1 & data[77] == 0 { aircraft.nav_altitude_src = None; }
+    if 2 & data[77] == 0 { aircraft.nav_heading = None; }
+    if 4 & data[77] == 0 { aircraft.nav_modes = Vec::new(); }
+    if 8 & data[77] == 0 { aircraft.alert1 = None; }
+    if 16 & data[77] == 0 {
+        aircraft.ws = None;
+        aircraft.wd = None;
+    }
+    if 32 & data[77] == 0 {
+        aircraft.oat = None;
+        aircraft.tat = None;
+    }
+
+    if aircraft.airground == AirGround::Ground {
+        aircraft.alt_baro_label = Some("ground");
+    }
+
+    if 4 & data[77] != 0 && groups.contains(FieldGroups::NAV) {
+        let nav_modes = data[66];
+
+        aircraft.nav_modes = vec![];
+
+        if 1 & nav_modes != 0 { aircraft.nav_modes.push("autopilot"); }
+        if 2 & nav_modes != 0 { aircraft.nav_modes.push("vnav"); }
+        if 4 & nav_modes != 0 { aircraft.nav_modes.push("alt_hold"); }
+        if 8 & nav_modes != 0 { aircraft.nav_modes.push("approach"); }
+        if 16 & nav_modes != 0 { aircraft.nav_modes.push("lnav"); }
+        if 32 & nav_modes != 0 { aircraft.nav_modes.push("tcas"); }
+    }
+
+    if !groups.contains(FieldGroups::META) {
+        return aircraft;
+    }
+
+    aircraft.signal_type =
+        Some(
+            match (240 & data[67]) >> 4 {
+                0 => SignalType::AdsbIcao,
+                1 => SignalType::AdsbIcaoNt,
+                2 => SignalType::AdsrIcao,
+                3 => SignalType::TisbIcao,
+                4 => SignalType::Adsc,
+                5 => SignalType::Mlat,
+                6 => SignalType::Other,
+                7 => SignalType::ModeS,
+                8 => SignalType::AdsbOther,
+                9 => SignalType::AdsrOther,
+                10 => SignalType::TisbTrackfile,
+                11 => SignalType::TisbOther,
+                12 => SignalType::ModeAc,
+                _ => SignalType::Unknown,
+            },
+        );
+
+    aircraft
+}
+
+/// The frame-level fields of a binCraft body, from [`parse_header`]. They
+/// have the same meaning as the fields of [`BinCraft`] with the same names.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BinCraftHeader {
+    pub now: f64,
+    pub stride: u32,
+    pub global_ac_count_withpos: u32,
+    pub globe_index: u32,
+    pub south: i16,
+    pub west: i16,
+    pub north: i16,
+    pub east: i16,
+    pub messages: u32,
+    pub receiver_lat: f64,
+    pub receiver_lon: f64,
+    pub bin_craft_version: u32,
+    /// Number of complete records following the header, which is how many
+    /// aircraft [`parse_adsb`] decodes unless the body is truncated.
+    pub aircraft_count: usize,
+}
+
+impl BinCraftHeader {
+    /// What [`decode_record`] needs to know about this frame, decoding only
+    /// the fields in `groups`.
+    pub fn context(&self, groups: FieldGroups) -> ParseContext {
+        ParseContext {
+            stride: self.stride,
+            globe_index: self.globe_index,
+            bin_craft_version: self.bin_craft_version,
+            groups,
+        }
+    }
+}
+
+/// Smallest stride that covers the fixed record offsets every binCraft
+/// version carries, up to the database flags. Fields past them are read
+/// only when the stride includes them.
+const MIN_STRIDE: u32 = 88;
+
+// The header occupies the first stride of the body, so a stride that is
+// long enough for a record also holds every header field.
+const _: () = assert!(MIN_STRIDE as usize >= HEADER_LEN);
+
+/// The fields at the start of the header, in readsb's layout. Every field
+/// is little-endian, and their offsets are fixed: the layout does not
+/// depend on the stride, which only decides where the first record starts.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub(crate) struct RawHeader {
+    /// Frame time in milliseconds, split into low and high words.
+    pub(crate) now_low: u32,
+    pub(crate) now_high: u32,
+    pub(crate) stride: u32,
+    pub(crate) global_ac_count_withpos: u32,
+    pub(crate) globe_index: u32,
+    pub(crate) south: i16,
+    pub(crate) west: i16,
+    pub(crate) north: i16,
+    pub(crate) east: i16,
+    pub(crate) messages: u32,
+    /// Receiver position in microdegrees, at bytes 32..36 and 36..40.
+    pub(crate) receiver_lat: i32,
+    pub(crate) receiver_lon: i32,
+    pub(crate) bin_craft_version: u32,
+}
+
+pub(crate) const HEADER_LEN: usize = std::mem::size_of::<RawHeader>();
+
+/// Decodes only the header of a decompressed binCraft body, for cheap
+/// probes of the frame time and message counts. No record is decoded.
+///
+/// Fails like [`parse_adsb`] on a body shorter than its first stride or
+/// with too small a stride, but not on a truncated record.
+pub fn parse_header(data: &[u8]) -> Result<BinCraftHeader, Error> {
+    if data.len() < HEADER_LEN {
+        return Err(Error::Truncated { needed: HEADER_LEN, available: data.len() });
+    }
+
+    // Response bodies carry no alignment guarantee, so read by copy.
+    let raw: RawHeader = bytemuck::pod_read_unaligned(&data[..HEADER_LEN]);
+
+    let stride = u32::from_le(raw.stride);
+
+    if stride < MIN_STRIDE {
+        return Err(Error::InvalidStride { stride });
+    }
+
+    if data.len() < stride as usize {
+        return Err(Error::Truncated { needed: stride as usize, available: data.len() });
+    }
+
+    Ok(BinCraftHeader {
+        now: u32::from_le(raw.now_low) as f64 / 1e3 + 4294967.296 * u32::from_le(raw.now_high) as f64,
+        stride,
+        global_ac_count_withpos: u32::from_le(raw.global_ac_count_withpos),
+        globe_index: u32::from_le(raw.globe_index),
+        south: i16::from_le(raw.south),
+        west: i16::from_le(raw.west),
+        north: i16::from_le(raw.north),
+        east: i16::from_le(raw.east),
+        messages: u32::from_le(raw.messages),
+        receiver_lat: i32::from_le(raw.receiver_lat) as f64 / POSITION_SCALE,
+        receiver_lon: i32::from_le(raw.receiver_lon) as f64 / POSITION_SCALE,
+        bin_craft_version: u32::from_le(raw.bin_craft_version),
+        aircraft_count: data.len() / stride as usize - 1,
+    })
+}
+
+/// Lazily decodes one aircraft per stride following the header.
+struct AircraftIter<'a> {
+    data: &'a [u8],
+    offset: usize,
+    ctx: ParseContext,
+}
+
+impl<'a> AircraftIter<'a> {
+    fn new(data: &'a [u8], header: &BinCraftHeader, groups: FieldGroups) -> Self {
+        AircraftIter {
+            data,
+            offset: header.stride as usize,
+            ctx: header.context(groups),
+        }
+    }
+}
+
+impl Iterator for AircraftIter<'_> {
+    type Item = Result<Aircraft, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stride = self.ctx.stride as usize;
+
+        if stride == 0 || self.offset >= self.data.len() {
+            return None;
+        }
+
+        let off = self.offset;
+        self.offset += stride;
+
+        let Some(record) = self.data.get(off..off + stride) else {
+            self.offset = self.data.len();
+
+            return Some(Err(Error::Truncated { needed: off + stride, available: self.data.len() }));
+        };
+
+        Some(Ok(build_aircraft(record, &self.ctx)))
+    }
+}
+
+/// Decodes a single record, for callers that split a body into records
+/// themselves, such as a custom streaming reader. `ctx` comes from the
+/// frame's header, via [`parse_header`] and [`BinCraftHeader::context`].
+///
+/// `data` must start at a record boundary, one or more strides after the
+/// header; only its first stride is read. A shorter slice fails with
+/// [`Error::Truncated`].
+///
+/// ```
+/// use adsbexchange::{decode_record, parse_header, FieldGroups};
+///
+/// # let mut data = vec![0u8; 224];
+/// # data[8..12].copy_from_slice(&112u32.to_le_bytes());
+/// # data[112..116].copy_from_slice(&0x4ca2d1u32.to_le_bytes());
+/// let header = parse_header(&data)?;
+/// let ctx = header.context(FieldGroups::ALL);
+/// let stride = ctx.stride() as usize;
+///
+/// let aircraft = decode_record(&data[stride..], &ctx)?;
+/// assert_eq!(aircraft.hex, "4ca2d1");
+/// # Ok::<(), adsbexchange::Error>(())
+/// ```
+pub fn decode_record(data: &[u8], ctx: &ParseContext) -> Result<Aircraft, Error> {
+    let stride = ctx.stride as usize;
+
+    let record = data.get(..stride).ok_or(Error::Truncated { needed: stride, available: data.len() })?;
+
+    Ok(build_aircraft(record, ctx))
+}
+
+/// Iterates over the aircraft records without collecting them, parsing the
+/// header once up front. A malformed header is yielded as a single error.
+pub fn iter_aircraft(data: &[u8]) -> impl Iterator<Item = Result<Aircraft, Error>> + '_ {
+    let (records, error) = match parse_header(data) {
+        Ok(header) => (Some(AircraftIter::new(data, &header, FieldGroups::ALL)), None),
+        Err(e) => (None, Some(Err(e))),
+    };
+
+    error.into_iter().chain(records.into_iter().flatten())
+}
+
+/// Parses a decompressed binCraft body.
+///
+/// Never panics on malformed input: a body shorter than the header or its
+/// first stride, or one ending inside a record, fails with
+/// [`Error::Truncated`], and a stride too small for a record with
+/// [`Error::InvalidStride`].
+pub fn parse_adsb(data: &[u8]) -> Result<BinCraft, Error> {
+    parse_adsb_with(data, FieldGroups::ALL)
+}
+
+/// Like [`parse_adsb`], decoding only the fields in `groups` and skipping
+/// the rest of each record, which saves most of the per-aircraft work when
+/// only positions and altitudes are wanted.
+pub fn parse_adsb_with(data: &[u8], groups: FieldGroups) -> Result<BinCraft, Error> {
+    let header = parse_header(data)?;
+
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("parse", stride = header.stride, bytes = data.len()).entered();
+    #[cfg(feature = "tracing")]
+    let started = std::time::Instant::now();
+
+    let aircraft = AircraftIter::new(data, &header, groups).collect::<Result<Vec<_>, _>>()?;
+
+    #[cfg(feature = "tracing")]
+    tracing::debug!(aircraft = aircraft.len(), elapsed = ?started.elapsed(), "parsed");
+
+    Ok(BinCraft {
+        now: header.now,
+        stride: header.stride,
+        global_ac_count_withpos: header.global_ac_count_withpos,
+        globe_index: header.globe_index,
+        south: header.south,
+        west: header.west,
+        north: header.north,
+        east: header.east,
+        messages: header.messages,
+        receiver_lat: header.receiver_lat,
+        receiver_lon: header.receiver_lon,
+        bin_craft_version: header.bin_craft_version,
+        fetched_at: None,
+        warnings: Vec::new(),
+        aircraft,
+    })
+}
+
+/// Longest gap between consecutive frames of a capture that
+/// [`iter_frames`] accepts when looking for the next header.
+const MAX_FRAME_GAP_SECS: f64 = 86_400.0;
+
+/// Whether the stride-sized slot at the start of `data` is the header of a
+/// frame following one with `header`, rather than one of its records.
+fn is_next_header(data: &[u8], header: &BinCraftHeader) -> bool {
+    let Ok(next) = parse_header(data) else {
+        return false;
+    };
+
+    next.stride == header.stride
+        && next.bin_craft_version == header.bin_craft_version
+        && (header.now..=header.now + MAX_FRAME_GAP_SECS).contains(&next.now)
+}
+
+/// Iterates over the frames of a capture made by concatenating several
+/// decompressed binCraft bodies, parsing each as [`parse_adsb`] does.
+///
+/// A binCraft header records neither its length nor its record count, so
+/// each frame is taken to end at the first record-sized slot that reads as
+/// a header with the same stride and format version and a `now` no earlier
+/// than the frame's, and at most a day later. A record mistaken for one
+/// would need a longitude equal to the stride in microdegrees and track and
+/// track rate bytes that spell out the version, which in practice does not
+/// happen. Frames of different strides or versions, as from captures
+/// mixing servers, are not split apart.
+///
+/// A malformed frame is yielded as an error and ends the iteration.
+pub fn iter_frames(data: &[u8]) -> impl Iterator<Item = Result<BinCraft, Error>> + '_ {
+    let mut rest = Some(data);
+
+    std::iter::from_fn(move || {
+        let data = rest.take().filter(|data| !data.is_empty())?;
+
+        let header = match parse_header(data) {
+            Ok(header) => header,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let stride = header.stride as usize;
+
+        let end = (stride..data.len())
+            .step_by(stride)
+            .find(|&offset| is_next_header(&data[offset..], &header))
+            .unwrap_or(data.len());
+
+        let frame = parse_adsb(&data[..end]);
+
+        if frame.is_ok() {
+            rest = Some(&data[end..]);
+        }
+
+        Some(frame)
+    })
+}
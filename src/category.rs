@@ -0,0 +1,105 @@
+use std::fmt;
+
+/// ADS-B emitter category, as tar1090 shows it (`A1`, `B6`, ...).
+///
+/// Named variants follow the DO-260B category sets; reserved codes and the
+/// "no information" code of each set are kept as `Other` with their raw
+/// byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Category {
+    /// A1, under 15 500 lb.
+    Light,
+    /// A2, 15 500 to 75 000 lb.
+    Small,
+    /// A3, 75 000 to 300 000 lb.
+    Large,
+    /// A4, high-vortex large aircraft such as the B757.
+    HighVortexLarge,
+    /// A5, over 300 000 lb.
+    Heavy,
+    /// A6, over 5 g and over 400 kt.
+    HighPerformance,
+    /// A7, helicopters and other rotorcraft.
+    Rotorcraft,
+    /// B1, gliders and sailplanes.
+    Glider,
+    /// B2, balloons and airships.
+    LighterThanAir,
+    /// B3, parachutists and skydivers.
+    Parachutist,
+    /// B4, ultralights, hang gliders and paragliders.
+    Ultralight,
+    /// B6, unmanned aerial vehicles.
+    Uav,
+    /// B7, space or trans-atmospheric vehicles.
+    Space,
+    /// C1, surface emergency vehicles.
+    EmergencyVehicle,
+    /// C2, surface service vehicles.
+    ServiceVehicle,
+    /// C3, point obstacles such as tethered balloons.
+    PointObstacle,
+    /// C4, cluster obstacles.
+    ClusterObstacle,
+    /// C5, line obstacles.
+    LineObstacle,
+    /// Any other code, including A0/B0/C0 (no information) and reserved ones.
+    Other(u8),
+}
+
+impl Category {
+    /// Decodes the category byte of a binCraft record, e.g. `0xA7`.
+    pub const fn from_raw(raw: u8) -> Self {
+        match raw {
+            0xa1 => Category::Light,
+            0xa2 => Category::Small,
+            0xa3 => Category::Large,
+            0xa4 => Category::HighVortexLarge,
+            0xa5 => Category::Heavy,
+            0xa6 => Category::HighPerformance,
+            0xa7 => Category::Rotorcraft,
+            0xb1 => Category::Glider,
+            0xb2 => Category::LighterThanAir,
+            0xb3 => Category::Parachutist,
+            0xb4 => Category::Ultralight,
+            0xb6 => Category::Uav,
+            0xb7 => Category::Space,
+            0xc1 => Category::EmergencyVehicle,
+            0xc2 => Category::ServiceVehicle,
+            0xc3 => Category::PointObstacle,
+            0xc4 => Category::ClusterObstacle,
+            0xc5 => Category::LineObstacle,
+            other => Category::Other(other),
+        }
+    }
+
+    pub const fn raw(&self) -> u8 {
+        match self {
+            Category::Light => 0xa1,
+            Category::Small => 0xa2,
+            Category::Large => 0xa3,
+            Category::HighVortexLarge => 0xa4,
+            Category::Heavy => 0xa5,
+            Category::HighPerformance => 0xa6,
+            Category::Rotorcraft => 0xa7,
+            Category::Glider => 0xb1,
+            Category::LighterThanAir => 0xb2,
+            Category::Parachutist => 0xb3,
+            Category::Ultralight => 0xb4,
+            Category::Uav => 0xb6,
+            Category::Space => 0xb7,
+            Category::EmergencyVehicle => 0xc1,
+            Category::ServiceVehicle => 0xc2,
+            Category::PointObstacle => 0xc3,
+            Category::ClusterObstacle => 0xc4,
+            Category::LineObstacle => 0xc5,
+            Category::Other(raw) => *raw,
+        }
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02X}", self.raw())
+    }
+}
@@ -0,0 +1,61 @@
+use crate::bincraft::BinCraft;
+
+/// Number of compass sectors in a coverage summary.
+pub const COVERAGE_SECTORS: usize = 16;
+
+/// Width of each sector in degrees.
+const SECTOR_DEG: f64 = 360.0 / COVERAGE_SECTORS as f64;
+
+/// Reception in one compass direction, from [`BinCraft::coverage`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CoverageSector {
+    /// Positioned aircraft in the sector.
+    pub count: usize,
+    /// Distance of the farthest of them in nautical miles, or `None` for an
+    /// empty sector.
+    pub max_range_nm: Option<f64>,
+}
+
+impl CoverageSector {
+    /// Bearing in degrees true at the middle of sector `index`: 0 for north,
+    /// 22.5 for north-northeast and so on clockwise.
+    pub fn center(index: usize) -> f64 {
+        index as f64 * SECTOR_DEG
+    }
+}
+
+impl BinCraft {
+    /// Reception by direction around the frame's own receiver, or `None` if
+    /// the frame carries no receiver position, as on aggregators; see
+    /// [`BinCraft::coverage_from`].
+    pub fn coverage(&self) -> Option<[CoverageSector; COVERAGE_SECTORS]> {
+        if self.receiver_lat == 0.0 && self.receiver_lon == 0.0 {
+            return None;
+        }
+
+        Some(self.coverage_from(self.receiver_lat, self.receiver_lon))
+    }
+
+    /// Buckets the positioned aircraft by bearing from the given point into
+    /// sixteen sectors centered on the compass points, starting at north,
+    /// with the count and farthest range in each. Sectors that only ever
+    /// see short ranges point at terrain or obstructions shadowing the
+    /// antenna; build the picture up over many frames for a fair view.
+    pub fn coverage_from(&self, lat: f64, lon: f64) -> [CoverageSector; COVERAGE_SECTORS] {
+        let mut sectors = [CoverageSector::default(); COVERAGE_SECTORS];
+
+        for aircraft in &self.aircraft {
+            let (Some(distance), Some(bearing)) = (aircraft.distance_from(lat, lon), aircraft.bearing_from(lat, lon)) else {
+                continue;
+            };
+
+            let index = ((bearing + SECTOR_DEG / 2.0) / SECTOR_DEG) as usize % COVERAGE_SECTORS;
+            let sector = &mut sectors[index];
+
+            sector.count += 1;
+            sector.max_range_nm = Some(sector.max_range_nm.map_or(distance, |max| max.max(distance)));
+        }
+
+        sectors
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use crate::aircraft::Aircraft;
+use crate::bincraft::{cmp_present, BinCraft};
+
+/// Which aircraft [`BinCraft::decimate`] keeps.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DecimateStrategy {
+    /// The positioned aircraft closest to a point, as in
+    /// [`BinCraft::nearest`].
+    Nearest { lat: f64, lon: f64 },
+    /// The highest aircraft by barometric altitude. Aircraft on the ground
+    /// or without an altitude come last; see
+    /// [`Aircraft::airborne_altitude`].
+    HighestAltitude,
+    /// At most one positioned aircraft per `degrees`-sized cell of latitude
+    /// and longitude, the most recently seen one, so that the result stays
+    /// spread over the whole area.
+    GridCell { degrees: f64 },
+}
+
+impl BinCraft {
+    /// Thins the frame out to at most `max` aircraft, for displays that
+    /// cannot render every aircraft of a dense area.
+    ///
+    /// `Nearest` returns aircraft nearest first and `HighestAltitude`
+    /// highest first. `GridCell` fills up to `max` cells, preferring those
+    /// whose aircraft was seen most recently, and returns them in frame
+    /// order.
+    pub fn decimate(&self, max: usize, strategy: DecimateStrategy) -> Vec<&Aircraft> {
+        match strategy {
+            DecimateStrategy::Nearest { lat, lon } => self.nearest(lat, lon, max),
+            DecimateStrategy::HighestAltitude => {
+                let mut aircraft = self.aircraft.iter().collect::<Vec<_>>();
+
+                aircraft.sort_by(|a, b| {
+                    cmp_present(a.airborne_altitude(), b.airborne_altitude(), |a, b| b.cmp(a))
+                });
+                aircraft.truncate(max);
+
+                aircraft
+            }
+            DecimateStrategy::GridCell { degrees } => {
+                let mut cells = HashMap::new();
+
+                for (i, aircraft) in self.aircraft.iter().enumerate() {
+                    let (Some(lat), Some(lon)) = (aircraft.lat, aircraft.lon) else {
+                        continue;
+                    };
+
+                    let cell = (
+                        ((lat as f64 + 90.0) / degrees).floor() as i64,
+                        ((lon as f64 + 180.0) / degrees).floor() as i64,
+                    );
+
+                    cells
+                        .entry(cell)
+                        .and_modify(|kept: &mut (usize, &Aircraft)| {
+                            if cmp_present(aircraft.seen, kept.1.seen, f32::total_cmp).is_lt() {
+                                *kept = (i, aircraft);
+                            }
+                        })
+                        .or_insert((i, aircraft));
+                }
+
+                let mut kept = cells.into_values().collect::<Vec<_>>();
+
+                kept.sort_by(|(i, a), (j, b)| cmp_present(a.seen, b.seen, f32::total_cmp).then(i.cmp(j)));
+                kept.truncate(max);
+                kept.sort_by_key(|(i, _)| *i);
+
+                kept.into_iter().map(|(_, aircraft)| aircraft).collect()
+            }
+        }
+    }
+}
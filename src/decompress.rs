@@ -0,0 +1,85 @@
+use std::cell::Cell;
+use std::io::{self, Read};
+use std::rc::Rc;
+
+use ruzstd::StreamingDecoder;
+
+use crate::error::Error;
+
+/// Magic number at the start of every zstd frame.
+pub(crate) const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Largest body [`decompress`] inflates, 64 MiB. A frame of the whole
+/// world is a few megabytes, so only a broken or hostile body gets near it.
+pub const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 64 << 20;
+
+/// Inflates a zstd-compressed binCraft body using the pure-Rust decoder,
+/// up to [`DEFAULT_MAX_DECOMPRESSED_SIZE`].
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, Error> {
+    decompress_with_limit(data, DEFAULT_MAX_DECOMPRESSED_SIZE)
+}
+
+/// Like [`decompress`], failing with [`Error::TooLarge`] as soon as the
+/// output would exceed `max_size` bytes rather than inflating the rest.
+pub fn decompress_with_limit(data: &[u8], max_size: usize) -> Result<Vec<u8>, Error> {
+    decompress_reader(data, max_size)
+}
+
+pub(crate) fn decompress_reader<R: Read>(reader: R, max_size: usize) -> Result<Vec<u8>, Error> {
+    read_limited(reader, |source| StreamingDecoder::new(source).map_err(|e| e.to_string()), max_size)
+}
+
+/// The compressed input as a decoder sees it. The first error reading it
+/// is kept aside, so a failing file or socket is reported as
+/// [`Error::Io`] rather than as the decoder's complaint about the input
+/// ending early.
+pub(crate) struct Source<R> {
+    inner: R,
+    error: Rc<Cell<Option<io::Error>>>,
+}
+
+impl<R: Read> Read for Source<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf).map_err(|e| {
+            // Retried by the decoder, so not a failure.
+            if e.kind() == io::ErrorKind::Interrupted {
+                return e;
+            }
+
+            let kind = e.kind();
+            self.error.set(Some(e));
+
+            io::Error::from(kind)
+        })
+    }
+}
+
+/// Inflates `reader` through the decoder `decoder` builds over it, stopping
+/// one byte past `max_size` to tell a body of exactly the limit from a
+/// larger one. Errors from the decoder fail with [`Error::Decompress`], and
+/// errors reading `reader` itself with [`Error::Io`].
+pub(crate) fn read_limited<R: Read, D: Read>(
+    reader: R,
+    decoder: impl FnOnce(Source<R>) -> Result<D, String>,
+    max_size: usize,
+) -> Result<Vec<u8>, Error> {
+    let error = Rc::default();
+    let source = Source { inner: reader, error: Rc::clone(&error) };
+
+    let failed = |message: String| match error.take() {
+        Some(e) => Error::Io(e),
+        None => Error::Decompress(message),
+    };
+
+    let decoder = decoder(source).map_err(failed)?;
+
+    let mut result = Vec::new();
+    decoder.take(max_size as u64 + 1).read_to_end(&mut result)
+        .map_err(|e| failed(e.to_string()))?;
+
+    if result.len() > max_size {
+        return Err(Error::TooLarge { limit: max_size });
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,98 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::aircraft::Aircraft;
+use crate::bincraft::BinCraft;
+use crate::geo;
+
+/// How far an aircraft must move between frames to be reported as changed.
+#[derive(Clone, Copy, Debug)]
+pub struct DiffThreshold {
+    /// Minimum great-circle movement in nautical miles.
+    pub distance_nm: f64,
+    /// Minimum change in barometric altitude in feet.
+    pub altitude_ft: i32,
+}
+
+impl Default for DiffThreshold {
+    /// Any movement readsb can resolve: about 20 m, or one 25 ft altitude
+    /// step.
+    fn default() -> Self {
+        DiffThreshold {
+            distance_nm: 0.01,
+            altitude_ft: 25,
+        }
+    }
+}
+
+/// What changed between two frames, keyed on `hex`.
+#[derive(Debug, Default)]
+pub struct Diff<'a> {
+    /// Hexes in the current frame but not the previous one.
+    pub added: HashSet<String>,
+    /// Hexes in the previous frame but not the current one.
+    pub removed: HashSet<String>,
+    /// Aircraft in both frames, as of the current frame, whose position or
+    /// altitude changed beyond the threshold, or which gained or lost
+    /// either.
+    pub changed: Vec<&'a Aircraft>,
+}
+
+impl Diff<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+fn has_changed(current: &Aircraft, previous: &Aircraft, threshold: &DiffThreshold) -> bool {
+    let moved = match (current.coordinates(), previous.coordinates()) {
+        (Some((lat1, lon1)), Some((lat2, lon2))) => {
+            geo::distance_nm(lat1, lon1, lat2, lon2) >= threshold.distance_nm
+        }
+        (None, None) => false,
+        _ => true,
+    };
+
+    let climbed = match (current.alt_baro, previous.alt_baro) {
+        (Some(a), Some(b)) => (a - b).abs() >= threshold.altitude_ft,
+        (None, None) => false,
+        _ => true,
+    };
+
+    moved || climbed
+}
+
+impl BinCraft {
+    /// Compares this frame against an earlier one using the default
+    /// [`DiffThreshold`].
+    pub fn diff<'a>(&'a self, previous: &BinCraft) -> Diff<'a> {
+        self.diff_with(previous, &DiffThreshold::default())
+    }
+
+    /// Compares this frame against an earlier one, reporting aircraft that
+    /// appeared, disappeared, or changed beyond `threshold`.
+    pub fn diff_with<'a>(&'a self, previous: &BinCraft, threshold: &DiffThreshold) -> Diff<'a> {
+        let mut previous = previous.aircraft
+            .iter()
+            .map(|a| (a.hex.as_str(), a))
+            .collect::<HashMap<_, _>>();
+
+        let mut diff = Diff::default();
+
+        for aircraft in &self.aircraft {
+            match previous.remove(aircraft.hex.as_str()) {
+                Some(before) => {
+                    if has_changed(aircraft, before, threshold) {
+                        diff.changed.push(aircraft);
+                    }
+                }
+                None => {
+                    diff.added.insert(aircraft.hex.clone());
+                }
+            }
+        }
+
+        diff.removed = previous.into_keys().map(str::to_string).collect();
+
+        diff
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The buffer ended before a complete header or record could be read.
+    Truncated { needed: usize, available: usize },
+    /// The header declares a record stride too small to hold an aircraft.
+    InvalidStride { stride: u32 },
+    /// The HTTP request failed.
+    #[cfg(feature = "net")]
+    Request(reqwest::Error),
+    /// The server answered with a non-success status.
+    #[cfg(feature = "net")]
+    Http { status: reqwest::StatusCode, body: String },
+    /// `fetch_boxes` was given no boxes to fetch.
+    #[cfg(feature = "net")]
+    NoBoxes,
+    /// The response body could not be decompressed.
+    Decompress(String),
+    /// The body inflates to more than `limit` bytes.
+    TooLarge { limit: usize },
+    /// A string is not a four-digit octal Mode A code.
+    InvalidSquawk(String),
+    /// A string is not a six-digit hex aircraft address.
+    InvalidIcao(String),
+    /// A globe index grid size is not a positive, finite number of degrees.
+    InvalidGrid { grid: f64 },
+    /// Reading the input failed.
+    Io(std::io::Error),
+    /// The input is not valid JSON.
+    #[cfg(feature = "serde")]
+    Json(serde_json::Error),
+    /// A trace file is valid JSON but not in the readsb trace shape.
+    #[cfg(feature = "serde")]
+    InvalidTrace(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Truncated { needed, available } => {
+                write!(f, "truncated binCraft data: needed {} bytes, {} available", needed, available)
+            }
+            Error::InvalidStride { stride } => write!(f, "invalid binCraft stride: {}", stride),
+            #[cfg(feature = "net")]
+            Error::Request(e) => write!(f, "request failed: {}", e),
+            #[cfg(feature = "net")]
+            Error::Http { status, body } => {
+                if body.is_empty() {
+                    write!(f, "HTTP {}", status)
+                } else {
+                    write!(f, "HTTP {}: {}", status, body)
+                }
+            }
+            #[cfg(feature = "net")]
+            Error::NoBoxes => write!(f, "no boxes to fetch"),
+            Error::Decompress(e) => write!(f, "decompression failed: {}", e),
+            Error::TooLarge { limit } => write!(f, "decompressed body exceeds {} bytes", limit),
+            Error::InvalidSquawk(s) => write!(f, "invalid squawk: {:?}", s),
+            Error::InvalidIcao(s) => write!(f, "invalid ICAO address: {:?}", s),
+            Error::InvalidGrid { grid } => write!(f, "invalid globe index grid: {}", grid),
+            Error::Io(e) => write!(f, "read failed: {}", e),
+            #[cfg(feature = "serde")]
+            Error::Json(e) => write!(f, "invalid JSON: {}", e),
+            #[cfg(feature = "serde")]
+            Error::InvalidTrace(reason) => write!(f, "invalid trace: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "net")]
+            Error::Request(e) => Some(e),
+            Error::Io(e) => Some(e),
+            #[cfg(feature = "serde")]
+            Error::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+#[cfg(feature = "net")]
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Error::Request(e)
+    }
+}
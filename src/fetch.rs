@@ -0,0 +1,602 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+#[cfg(feature = "blocking")]
+use std::sync::OnceLock;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use bytes::Bytes;
+use futures_util::future::try_join_all;
+use reqwest::header::{HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+
+use crate::bincraft::{parse_adsb, BinCraft};
+use crate::decompress::{decompress_with_limit, DEFAULT_MAX_DECOMPRESSED_SIZE, ZSTD_MAGIC};
+use crate::error::Error;
+use crate::geo::BoundingBox;
+#[cfg(feature = "serde")]
+use crate::icao::Icao;
+use crate::merge::MergePolicy;
+use crate::query::QueryBuilder;
+#[cfg(feature = "metrics")]
+use crate::metrics::Metrics;
+#[cfg(feature = "serde")]
+use crate::trace::{parse_trace_with_limit, Trace};
+
+pub const DEFAULT_BASE_URL: &str = "https://globe.adsbexchange.com/re-api/";
+
+/// Header carrying the key for paid adsbexchange feed access.
+const API_KEY_HEADER: &str = "api-auth";
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Root of the re-api, e.g. `http://my-pi:8080/re-api/` for a self-hosted
+    /// readsb/tar1090 instance. Defaults to the public adsbexchange host.
+    pub base_url: String,
+    /// Key for authenticated feed access, sent with every request when set.
+    pub api_key: Option<String>,
+    /// Proxy for every request, e.g. `http://proxy:3128` or
+    /// `socks5h://127.0.0.1:9050`. When unset, the `HTTP_PROXY`,
+    /// `HTTPS_PROXY` and `NO_PROXY` environment variables are honoured.
+    pub proxy: Option<String>,
+    /// Drop aircraft without a position from every fetched frame. Header
+    /// fields such as `global_ac_count_withpos` are left as the server sent
+    /// them.
+    pub positioned_only: bool,
+    /// Send `If-None-Match`/`If-Modified-Since` when repeating a request,
+    /// and reuse the previous body when the server answers `304 Not
+    /// Modified`. Keeps the last body of each of the
+    /// [`MAX_CACHED_RESPONSES`] most recently repeated requests in memory,
+    /// so polling ever-changing boxes does not grow the cache without bound.
+    pub conditional: bool,
+    /// Largest size a zstd body or gzipped trace may inflate to before the
+    /// fetch fails with [`Error::TooLarge`], guarding against a server
+    /// sending a small body that expands without bound.
+    pub max_decompressed_size: usize,
+    /// How many degrees an edge of the area the server reports covering may
+    /// differ from the requested box before [`fetch_box`] adds a
+    /// [`Warning::BoxClamped`](crate::Warning::BoxClamped) to the frame.
+    /// The header stores whole degrees, so the default of 1 allows for
+    /// rounding; `f64::INFINITY` turns the check off.
+    pub box_clamp_tolerance_deg: f64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            api_key: None,
+            proxy: None,
+            positioned_only: false,
+            conditional: true,
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+            box_clamp_tolerance_deg: 1.0,
+        }
+    }
+}
+
+/// How many distinct requests a [`Client`] keeps the last response of for
+/// conditional GETs. A poller usually repeats a handful of requests, and
+/// the least recently used is dropped to make room for a new one.
+pub const MAX_CACHED_RESPONSES: usize = 16;
+
+/// The validators and body of the last successful response for a URL.
+#[derive(Clone, Debug)]
+struct Cached {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+    body: Bytes,
+}
+
+/// The last responses for up to [`MAX_CACHED_RESPONSES`] URLs, least
+/// recently used first.
+#[derive(Debug, Default)]
+struct ResponseCache {
+    entries: VecDeque<(String, Cached)>,
+}
+
+impl ResponseCache {
+    /// The response for `url`, marking it as the most recently used.
+    fn get(&mut self, url: &str) -> Option<Cached> {
+        let i = self.entries.iter().position(|(cached_url, _)| cached_url == url)?;
+        let entry = self.entries.remove(i)?;
+        let cached = entry.1.clone();
+
+        self.entries.push_back(entry);
+
+        Some(cached)
+    }
+
+    fn insert(&mut self, url: &str, cached: Cached) {
+        self.remove(url);
+
+        if self.entries.len() >= MAX_CACHED_RESPONSES {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back((url.to_string(), cached));
+    }
+
+    fn remove(&mut self, url: &str) {
+        self.entries.retain(|(cached_url, _)| cached_url != url);
+    }
+}
+
+/// An HTTP client bundled with the configuration used for every fetch.
+#[derive(Clone, Debug, Default)]
+pub struct Client {
+    http: reqwest::Client,
+    config: Config,
+    /// Shared between clones, so every clone benefits from conditional GETs.
+    cache: Arc<Mutex<ResponseCache>>,
+    /// Built on the first blocking fetch, since a blocking client cannot be
+    /// created inside an async runtime.
+    #[cfg(feature = "blocking")]
+    blocking: Arc<OnceLock<reqwest::blocking::Client>>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Metrics>>,
+}
+
+impl Client {
+    pub fn new() -> Self {
+        Client::default()
+    }
+
+    /// A client using `config`. Fails if the proxy URL is invalid.
+    pub fn with_config(config: Config) -> Result<Self, Error> {
+        let mut http = reqwest::Client::builder();
+
+        if let Some(proxy) = &config.proxy {
+            http = http.proxy(reqwest::Proxy::all(proxy)?);
+        }
+
+        Ok(Client {
+            http: http.build()?,
+            config,
+            cache: Arc::default(),
+            #[cfg(feature = "blocking")]
+            blocking: Arc::default(),
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        })
+    }
+
+    /// Records every fetch made through this client in `metrics`.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    async fn get(&self, query: &QueryBuilder) -> Result<BinCraft, Error> {
+        let url = query.url(&self.config.base_url);
+
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            let started = std::time::Instant::now();
+            let result = self.fetch(&url).await;
+
+            metrics.observe_fetch(&result, started.elapsed());
+
+            return result;
+        }
+
+        self.fetch(&url).await
+    }
+
+    async fn fetch(&self, url: &str) -> Result<BinCraft, Error> {
+        let data = self.fetch_body(url).await?;
+
+        self.decode(url, &data)
+    }
+
+    /// Warns on `frame` if the server covered a different area than
+    /// `requested`.
+    fn check_box(&self, frame: &mut BinCraft, requested: &BoundingBox) {
+        let Some(warning) = frame.box_clamp(requested, self.config.box_clamp_tolerance_deg) else {
+            return;
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::warn!(%warning, "box clamped");
+
+        frame.warnings.push(warning);
+    }
+
+    /// Decompresses and parses a body fetched from `url`.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn decode(&self, url: &str, data: &[u8]) -> Result<BinCraft, Error> {
+        let fetched_at = SystemTime::now();
+
+        #[cfg(feature = "tracing")]
+        let started = std::time::Instant::now();
+
+        // Servers that ignore the zstd parameter, and self-hosted readsb
+        // instances without it, answer with plain binCraft.
+        let decompressed = if data.starts_with(&ZSTD_MAGIC) {
+            Cow::Owned(decompress_with_limit(data, self.config.max_decompressed_size)?)
+        } else {
+            Cow::Borrowed(data)
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            %url,
+            bytes = data.len(),
+            decompressed = decompressed.len(),
+            elapsed = ?started.elapsed(),
+            "decompressed",
+        );
+
+        let mut frame = parse_adsb(&decompressed)?;
+        frame.fetched_at = Some(fetched_at);
+
+        if self.config.positioned_only {
+            frame.aircraft.retain(|a| a.lat.is_some() && a.lon.is_some());
+        }
+
+        Ok(frame)
+    }
+
+    /// The body at `url`, revalidating the previous response for it when
+    /// conditional requests are enabled.
+    async fn fetch_body(&self, url: &str) -> Result<Bytes, Error> {
+        if !self.config.conditional {
+            return Ok(self.send(url, None).await?.bytes().await?);
+        }
+
+        let cached = self.cache.lock().unwrap().get(url);
+        let res = self.send(url, cached.as_ref()).await?;
+
+        if let (StatusCode::NOT_MODIFIED, Some(cached)) = (res.status(), cached) {
+            return Ok(cached.body);
+        }
+
+        let headers = res.headers().clone();
+        let body = res.bytes().await?;
+
+        self.remember(url, &headers, &body);
+
+        Ok(body)
+    }
+
+    /// Keeps `body` for revalidating later requests for `url`, if the
+    /// response came with validators.
+    fn remember(&self, url: &str, headers: &HeaderMap, body: &Bytes) {
+        let etag = headers.get(ETAG).cloned();
+        let last_modified = headers.get(LAST_MODIFIED).cloned();
+
+        let mut cache = self.cache.lock().unwrap();
+
+        if etag.is_some() || last_modified.is_some() {
+            cache.insert(url, Cached { etag, last_modified, body: body.clone() });
+        } else {
+            cache.remove(url);
+        }
+    }
+
+    /// The validators to make a request conditional on `cached`, if any.
+    fn conditional_headers(cached: Option<&Cached>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+
+        if let Some(cached) = cached {
+            if let Some(etag) = &cached.etag {
+                headers.insert(IF_NONE_MATCH, etag.clone());
+            }
+
+            if let Some(last_modified) = &cached.last_modified {
+                headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+            }
+        }
+
+        headers
+    }
+
+    /// Requests `url` with the configured API key, failing on a non-success
+    /// status. With `cached`, the request is made conditional on its
+    /// validators and a `304 Not Modified` is passed through.
+    async fn send(&self, url: &str, cached: Option<&Cached>) -> Result<reqwest::Response, Error> {
+        let mut request = self.http.get(url).headers(Client::conditional_headers(cached));
+
+        if let Some(api_key) = &self.config.api_key {
+            request = request.header(API_KEY_HEADER, api_key);
+        }
+
+        #[cfg(feature = "tracing")]
+        let started = std::time::Instant::now();
+
+        let res = request
+            .send()
+            .await?;
+
+        let status = res.status();
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(%url, %status, elapsed = ?started.elapsed(), "fetch response");
+
+        if status == StatusCode::NOT_MODIFIED && cached.is_some() {
+            return Ok(res);
+        }
+
+        if !status.is_success() {
+            let body = res.text().await.unwrap_or_default();
+
+            #[cfg(feature = "tracing")]
+            tracing::warn!(%url, %status, "fetch failed");
+
+            return Err(Error::Http { status, body });
+        }
+
+        Ok(res)
+    }
+
+    /// The site root the re-api lives under, where readsb also serves its
+    /// trace files.
+    #[cfg(feature = "serde")]
+    fn site_url(&self) -> &str {
+        let base = &self.config.base_url;
+
+        base.strip_suffix("re-api/")
+            .or_else(|| base.strip_suffix("re-api"))
+            .unwrap_or(base)
+    }
+}
+
+/// The synchronous counterparts of the async fetch path, sharing its
+/// query building, conditional requests, decompression and parsing.
+#[cfg(feature = "blocking")]
+impl Client {
+    fn blocking_http(&self) -> Result<&reqwest::blocking::Client, Error> {
+        if let Some(http) = self.blocking.get() {
+            return Ok(http);
+        }
+
+        let mut http = reqwest::blocking::Client::builder();
+
+        if let Some(proxy) = &self.config.proxy {
+            http = http.proxy(reqwest::Proxy::all(proxy)?);
+        }
+
+        let http = http.build()?;
+
+        Ok(self.blocking.get_or_init(|| http))
+    }
+
+    fn get_blocking(&self, query: &QueryBuilder) -> Result<BinCraft, Error> {
+        let url = query.url(&self.config.base_url);
+
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            let started = std::time::Instant::now();
+            let result = self.fetch_blocking(&url);
+
+            metrics.observe_fetch(&result, started.elapsed());
+
+            return result;
+        }
+
+        self.fetch_blocking(&url)
+    }
+
+    fn fetch_blocking(&self, url: &str) -> Result<BinCraft, Error> {
+        let data = self.fetch_body_blocking(url)?;
+
+        self.decode(url, &data)
+    }
+
+    fn fetch_body_blocking(&self, url: &str) -> Result<Bytes, Error> {
+        if !self.config.conditional {
+            return Ok(self.send_blocking(url, None)?.bytes()?);
+        }
+
+        let cached = self.cache.lock().unwrap().get(url);
+        let res = self.send_blocking(url, cached.as_ref())?;
+
+        if let (StatusCode::NOT_MODIFIED, Some(cached)) = (res.status(), cached) {
+            return Ok(cached.body);
+        }
+
+        let headers = res.headers().clone();
+        let body = res.bytes()?;
+
+        self.remember(url, &headers, &body);
+
+        Ok(body)
+    }
+
+    fn send_blocking(&self, url: &str, cached: Option<&Cached>) -> Result<reqwest::blocking::Response, Error> {
+        let mut request = self.blocking_http()?.get(url).headers(Client::conditional_headers(cached));
+
+        if let Some(api_key) = &self.config.api_key {
+            request = request.header(API_KEY_HEADER, api_key);
+        }
+
+        let res = request.send()?;
+        let status = res.status();
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(%url, %status, "fetch response");
+
+        if status == StatusCode::NOT_MODIFIED && cached.is_some() {
+            return Ok(res);
+        }
+
+        if !status.is_success() {
+            let body = res.text().unwrap_or_default();
+
+            #[cfg(feature = "tracing")]
+            tracing::warn!(%url, %status, "fetch failed");
+
+            return Err(Error::Http { status, body });
+        }
+
+        Ok(res)
+    }
+}
+
+/// Fetches whatever `query` selects, for requests the other `fetch_*`
+/// functions do not cover.
+pub async fn fetch_query(client: &Client, query: &QueryBuilder) -> Result<BinCraft, Error> {
+    client.get(query).await
+}
+
+/// Fetches all aircraft inside the given box.
+///
+/// Servers clamp boxes larger than they are configured to serve. When the
+/// area the response covers differs from `bbox` by more than
+/// `Config::box_clamp_tolerance_deg`, the frame carries a
+/// [`Warning::BoxClamped`](crate::Warning::BoxClamped) in `warnings`, also
+/// logged with the `tracing` feature.
+pub async fn fetch_box(client: &Client, bbox: &BoundingBox) -> Result<BinCraft, Error> {
+    let mut frame = client.get(&QueryBuilder::bbox(bbox)).await?;
+    client.check_box(&mut frame, bbox);
+
+    Ok(frame)
+}
+
+/// Like [`fetch_box`], but splits a box that crosses the antimeridian into
+/// two requests, issued concurrently, and merges the results with the
+/// default [`MergePolicy`].
+pub async fn fetch_box_split(client: &Client, bbox: &BoundingBox) -> Result<BinCraft, Error> {
+    let boxes = bbox.split_antimeridian();
+
+    let [west, east] = boxes.as_slice() else {
+        return fetch_box(client, bbox).await;
+    };
+
+    let (mut frame, other) = tokio::try_join!(fetch_box(client, west), fetch_box(client, east))?;
+    frame.merge(other, &MergePolicy::default());
+
+    Ok(frame)
+}
+
+/// Fetches `bbox` as in [`fetch_box_split`] and keeps only the aircraft
+/// registered as `registration`, compared ignoring case.
+///
+/// This filters the box on the client, so it finds the aircraft only while
+/// it is inside `bbox`, and every fetch still costs the whole box. Servers
+/// that support it can look a registration up worldwide instead, with
+/// [`QueryBuilder::registration`] and [`fetch_query`].
+pub async fn fetch_by_registration(client: &Client, bbox: &BoundingBox, registration: &str) -> Result<BinCraft, Error> {
+    let registration = registration.trim();
+
+    let mut frame = fetch_box_split(client, bbox).await?;
+    frame.aircraft.retain(|a| !a.registration.is_empty() && a.registration.eq_ignore_ascii_case(registration));
+
+    Ok(frame)
+}
+
+/// Fetches `bbox` as in [`fetch_box_split`] and keeps only the aircraft
+/// flying as `callsign`, compared as decoded: trimmed and ignoring case.
+///
+/// Like [`fetch_by_registration`] this filters on the client; see
+/// [`QueryBuilder::callsign`] for a server-side lookup.
+pub async fn fetch_by_callsign(client: &Client, bbox: &BoundingBox, callsign: &str) -> Result<BinCraft, Error> {
+    let mut frame = fetch_box_split(client, bbox).await?;
+    frame.aircraft.retain(|a| a.flight.as_deref().is_some_and(|flight| flight.eq_ignore_ascii_case(callsign.trim())));
+
+    Ok(frame)
+}
+
+/// Fetches several boxes concurrently, each as in [`fetch_box_split`], and
+/// merges them into one snapshot with [`BinCraft::merge_newest`].
+///
+/// Aircraft in the overlap of two boxes appear once, as reported by the
+/// fresher of the two responses, so a region tiled by overlapping boxes
+/// comes back without duplicates. Header fields other than `now` and
+/// `fetched_at` are those of the first box. Aircraft are sorted with
+/// [`BinCraft::sort_by_hex`], so the result does not depend on which
+/// response arrived first. Fails if any box fails, and with
+/// [`Error::NoBoxes`] if `boxes` is empty, without a request.
+pub async fn fetch_boxes(client: &Client, boxes: &[BoundingBox]) -> Result<BinCraft, Error> {
+    let frames = try_join_all(boxes.iter().map(|bbox| fetch_box_split(client, bbox))).await?;
+
+    let mut frames = frames.into_iter();
+    let Some(mut merged) = frames.next() else {
+        return Err(Error::NoBoxes);
+    };
+
+    for frame in frames {
+        merged.merge_newest(frame);
+    }
+
+    merged.sort_by_hex();
+
+    Ok(merged)
+}
+
+/// Fetches every aircraft the server currently tracks, with or without a
+/// position, in one request.
+///
+/// Each record takes 112 bytes, so a worldwide aggregator tracking around
+/// 10,000 aircraft returns about a megabyte of binCraft, compressed to a
+/// few hundred kilobytes on the wire. Callers that only need part of the
+/// world, or poll often, are better served by [`fetch_box`] or
+/// [`fetch_boxes`].
+///
+/// Aircraft are sorted with [`BinCraft::sort_by_hex`].
+pub async fn fetch_all_visible(client: &Client) -> Result<BinCraft, Error> {
+    let mut frame = client.get(&QueryBuilder::all()).await?;
+    frame.sort_by_hex();
+
+    Ok(frame)
+}
+
+/// Fetches the aircraft with the given ICAO hex addresses.
+pub async fn fetch_hex(client: &Client, hex: &[&str]) -> Result<BinCraft, Error> {
+    client.get(&QueryBuilder::hex(hex)).await
+}
+
+/// Like [`fetch_query`], blocking the current thread. Must not be called
+/// from within an async runtime.
+#[cfg(feature = "blocking")]
+pub fn fetch_query_blocking(client: &Client, query: &QueryBuilder) -> Result<BinCraft, Error> {
+    client.get_blocking(query)
+}
+
+/// Like [`fetch_box`], blocking the current thread. Must not be called from
+/// within an async runtime.
+#[cfg(feature = "blocking")]
+pub fn fetch_box_blocking(client: &Client, bbox: &BoundingBox) -> Result<BinCraft, Error> {
+    let mut frame = client.get_blocking(&QueryBuilder::bbox(bbox))?;
+    client.check_box(&mut frame, bbox);
+
+    Ok(frame)
+}
+
+/// Like [`fetch_hex`], blocking the current thread. Must not be called from
+/// within an async runtime.
+#[cfg(feature = "blocking")]
+pub fn fetch_hex_blocking(client: &Client, hex: &[&str]) -> Result<BinCraft, Error> {
+    client.get_blocking(&QueryBuilder::hex(hex))
+}
+
+/// Fetches the position history of one aircraft.
+///
+/// With no `date` this is the recent trace covering roughly the last hour;
+/// with a `(year, month, day)` it is the full trace for that UTC day from
+/// the history archive. Trace files are served gzipped, so decoding them
+/// needs the `gzip` feature.
+///
+/// Fails with [`Error::InvalidIcao`], before any request, unless `hex` is
+/// an address as [`Icao`] parses it.
+#[cfg(feature = "serde")]
+pub async fn fetch_trace(client: &Client, hex: &str, date: Option<(u16, u8, u8)>) -> Result<Trace, Error> {
+    let hex = hex.trim().parse::<Icao>()?.to_string();
+    let bucket = &hex[hex.len() - 2..];
+
+    let path = match date {
+        None => format!("data/traces/{}/trace_recent_{}.json", bucket, hex),
+        Some((year, month, day)) => {
+            format!("globe_history/{}/{:02}/{:02}/traces/{}/trace_full_{}.json", year, month, day, bucket, hex)
+        }
+    };
+
+    let res = client.send(&format!("{}{}", client.site_url(), path), None).await?;
+
+    parse_trace_with_limit(&res.bytes().await?, client.config.max_decompressed_size)
+}
@@ -0,0 +1,40 @@
+use std::ops::BitOr;
+
+/// Groups of [`Aircraft`](crate::Aircraft) fields to decode, for
+/// [`parse_adsb_with`](crate::parse_adsb_with). Combine groups with `|`.
+///
+/// `hex`, the position with `seen` and `seen_pos`, `alt_baro`, `alt_geom`
+/// and `airground` are always decoded. Fields of groups that are left out
+/// stay `None`, empty or zero, as if the server had not reported them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FieldGroups(u8);
+
+impl FieldGroups {
+    /// Only the fields that are always decoded.
+    pub const NONE: FieldGroups = FieldGroups(0);
+    /// Speeds, track, headings, roll, vertical rates, wind and
+    /// temperatures.
+    pub const VELOCITY: FieldGroups = FieldGroups(1);
+    /// The `nav_*` autopilot settings.
+    pub const NAV: FieldGroups = FieldGroups(2);
+    /// Everything in [`QualityMetrics`](crate::QualityMetrics).
+    pub const QUALITY: FieldGroups = FieldGroups(4);
+    /// Callsign, squawk, emergency, category, signal type, database fields,
+    /// message counts, reception details, and the alert and ident bits.
+    pub const META: FieldGroups = FieldGroups(8);
+    /// Every field, as [`parse_adsb`](crate::parse_adsb) decodes.
+    pub const ALL: FieldGroups = FieldGroups(15);
+
+    /// Whether every group in `other` is included.
+    pub fn contains(self, other: FieldGroups) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for FieldGroups {
+    type Output = FieldGroups;
+
+    fn bitor(self, other: FieldGroups) -> FieldGroups {
+        FieldGroups(self.0 | other.0)
+    }
+}
@@ -0,0 +1,126 @@
+/// Mean Earth radius in nautical miles.
+pub(crate) const EARTH_RADIUS_NM: f64 = 3440.065;
+
+/// Great-circle distance between two points in nautical miles.
+pub(crate) fn distance_nm(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (lon2 - lon1).to_radians();
+
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+
+    2.0 * EARTH_RADIUS_NM * a.sqrt().asin()
+}
+
+/// Initial great-circle bearing in degrees true, `0..360`, from the first
+/// point towards the second.
+pub(crate) fn initial_bearing(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lon = (lon2 - lon1).to_radians();
+
+    let y = d_lon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * d_lon.cos();
+
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
+/// Point reached by travelling `distance` nautical miles from a start point
+/// along the initial bearing `bearing` (degrees true).
+pub(crate) fn destination(lat: f64, lon: f64, bearing: f64, distance: f64) -> (f64, f64) {
+    let lat1 = lat.to_radians();
+    let lon1 = lon.to_radians();
+    let theta = bearing.to_radians();
+    let delta = distance / EARTH_RADIUS_NM;
+
+    let lat2 = (lat1.sin() * delta.cos() + lat1.cos() * delta.sin() * theta.cos()).asin();
+    let lon2 = lon1 + (theta.sin() * delta.sin() * lat1.cos()).atan2(delta.cos() - lat1.sin() * lat2.sin());
+
+    (lat2.to_degrees(), (lon2.to_degrees() + 540.0) % 360.0 - 180.0)
+}
+
+/// Digits of a geohash, five bits each.
+const GEOHASH_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Longest geohash worth computing: twelve characters are already a few
+/// centimetres, finer than an `f32` position.
+pub(crate) const MAX_GEOHASH_PRECISION: usize = 12;
+
+/// The geohash of a point, `precision` characters long, at most
+/// [`MAX_GEOHASH_PRECISION`].
+pub(crate) fn geohash(lat: f64, lon: f64, precision: usize) -> String {
+    let (mut lat_range, mut lon_range) = ((-90.0, 90.0), (-180.0, 180.0));
+    let mut hash = String::with_capacity(precision);
+
+    // Bits alternate between longitude and latitude, longitude first,
+    // halving the range each time.
+    let mut even = true;
+
+    for _ in 0..precision.min(MAX_GEOHASH_PRECISION) {
+        let mut digit = 0;
+
+        for _ in 0..5 {
+            let (range, value) = if even { (&mut lon_range, lon) } else { (&mut lat_range, lat) };
+            let mid = (range.0 + range.1) / 2.0;
+
+            digit <<= 1;
+
+            if value >= mid {
+                digit |= 1;
+                range.0 = mid;
+            } else {
+                range.1 = mid;
+            }
+
+            even = !even;
+        }
+
+        hash.push(GEOHASH_ALPHABET[digit] as char);
+    }
+
+    hash
+}
+
+/// A latitude/longitude rectangle in degrees.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingBox {
+    pub south: f64,
+    pub north: f64,
+    pub west: f64,
+    pub east: f64,
+}
+
+impl BoundingBox {
+    pub fn new(south: f64, north: f64, west: f64, east: f64) -> Self {
+        BoundingBox { south, north, west, east }
+    }
+
+    /// Whether the box wraps across the 180° meridian, i.e. `west > east`.
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.west > self.east
+    }
+
+    /// Whether the point lies inside the box, edges included.
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        let in_lon = if self.crosses_antimeridian() {
+            lon >= self.west || lon <= self.east
+        } else {
+            lon >= self.west && lon <= self.east
+        };
+
+        lat >= self.south && lat <= self.north && in_lon
+    }
+
+    /// Splits a box that crosses the antimeridian into the part east of
+    /// `west` up to 180° and the part from -180° up to `east`. Boxes that do
+    /// not cross are returned unchanged as the only element.
+    pub fn split_antimeridian(&self) -> Vec<BoundingBox> {
+        if !self.crosses_antimeridian() {
+            return vec![*self];
+        }
+
+        vec![
+            BoundingBox::new(self.south, self.north, self.west, 180.0),
+            BoundingBox::new(self.south, self.north, -180.0, self.east),
+        ]
+    }
+}
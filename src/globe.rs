@@ -0,0 +1,133 @@
+use crate::error::Error;
+use crate::geo::BoundingBox;
+
+/// Indices of plain grid tiles start here; lower indices name special tiles.
+const GRID_INDEX_BASE: u32 = 1000;
+
+/// The tile layout behind [`BinCraft::globe_index`](crate::BinCraft::globe_index),
+/// as readsb assigns it.
+///
+/// The globe is cut into `grid`-degree cells. Cells that fall inside one of
+/// the `special_tiles` belong to that tile, whose index is its position in
+/// the list; every other cell is a tile of its own, numbered from 1000 up.
+/// Both values are server configuration, published by readsb in
+/// `receiver.json` as `globeIndexGrid` and `globeIndexSpecialTiles`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlobeIndex {
+    grid: f64,
+    special_tiles: Vec<BoundingBox>,
+}
+
+impl GlobeIndex {
+    /// The layout with `grid`-degree cells and the given special tiles.
+    /// Fails with [`Error::InvalidGrid`] unless `grid` is positive and
+    /// finite.
+    pub fn new(grid: f64, special_tiles: Vec<BoundingBox>) -> Result<Self, Error> {
+        if !(grid.is_finite() && grid > 0.0) {
+            return Err(Error::InvalidGrid { grid });
+        }
+
+        Ok(GlobeIndex { grid, special_tiles })
+    }
+
+    /// Cell size in degrees.
+    pub fn grid(&self) -> f64 {
+        self.grid
+    }
+
+    /// Larger tiles covering sparse areas, in the server's order. A tile
+    /// may cross the antimeridian (`west > east`).
+    pub fn special_tiles(&self) -> &[BoundingBox] {
+        &self.special_tiles
+    }
+
+    /// Cells per row of latitude, with readsb's extra column.
+    fn lat_multiplier(&self) -> u32 {
+        (360.0 / self.grid) as u32 + 1
+    }
+
+    /// The tile containing the given position.
+    pub fn index_of(&self, lat: f64, lon: f64) -> u32 {
+        let i = ((lat + 90.0) / self.grid).floor().max(0.0);
+        let j = ((lon + 180.0) / self.grid).floor().max(0.0);
+
+        // Special tiles are matched against the cell's corner, not the
+        // position itself.
+        let lat = i * self.grid - 90.0;
+        let lon = j * self.grid - 180.0;
+
+        let special = self.special_tiles.iter().position(|tile| {
+            lat >= tile.south && lat < tile.north && if tile.crosses_antimeridian() {
+                lon >= tile.west || lon < tile.east
+            } else {
+                lon >= tile.west && lon < tile.east
+            }
+        });
+
+        match special {
+            Some(index) => index as u32,
+            None => i as u32 * self.lat_multiplier() + j as u32 + GRID_INDEX_BASE,
+        }
+    }
+
+    /// The area a tile covers, or `None` for indices that name no tile. For
+    /// a grid cell inside a special tile, this is still the cell itself.
+    pub fn bounds(&self, index: u32) -> Option<BoundingBox> {
+        if index < GRID_INDEX_BASE {
+            return self.special_tiles.get(index as usize).copied();
+        }
+
+        let cell = index - GRID_INDEX_BASE;
+        let (i, j) = (cell / self.lat_multiplier(), cell % self.lat_multiplier());
+
+        let south = i as f64 * self.grid - 90.0;
+        let west = j as f64 * self.grid - 180.0;
+
+        if south >= 90.0 || west >= 180.0 {
+            return None;
+        }
+
+        Some(BoundingBox::new(south, (south + self.grid).min(90.0), west, (west + self.grid).min(180.0)))
+    }
+
+    /// Every tile overlapping `bbox`, in ascending order, so a large area can
+    /// be fetched one tile at a time without overlap. A box with an edge
+    /// that is NaN or infinite covers no tiles.
+    pub fn tiles_covering(&self, bbox: &BoundingBox) -> Vec<u32> {
+        let mut tiles = Vec::new();
+
+        if ![bbox.south, bbox.north, bbox.west, bbox.east].iter().all(|edge| edge.is_finite()) {
+            return tiles;
+        }
+
+        for part in bbox.split_antimeridian() {
+            // Step through the cells by their corners, taking at least one
+            // cell in each direction so a zero-sized box still has a tile.
+            let mut lat = ((part.south + 90.0) / self.grid).floor() * self.grid - 90.0;
+
+            loop {
+                let mut lon = ((part.west + 180.0) / self.grid).floor() * self.grid - 180.0;
+
+                loop {
+                    tiles.push(self.index_of(lat, lon));
+                    lon += self.grid;
+
+                    if lon >= part.east.min(180.0) {
+                        break;
+                    }
+                }
+
+                lat += self.grid;
+
+                if lat >= part.north.min(90.0) {
+                    break;
+                }
+            }
+        }
+
+        tiles.sort_unstable();
+        tiles.dedup();
+
+        tiles
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Error;
+
+/// Bit readsb sets on addresses that are not ICAO-assigned, such as TIS-B
+/// track numbers, which it shows with a `~` prefix.
+const NON_ICAO: u32 = 1 << 24;
+
+/// A 24-bit aircraft address as a map key, stored as in binCraft: the
+/// address plus the non-ICAO flag, so `4ca2d6` and `~4ca2d6` are distinct
+/// keys and `4CA2D6` is the same key as `4ca2d6`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Icao(u32);
+
+impl Icao {
+    /// Wraps the first word of a binCraft record, ignoring the unrelated
+    /// bits above the flag.
+    pub const fn from_raw(raw: u32) -> Self {
+        Icao(raw & (NON_ICAO | 0xffffff))
+    }
+
+    pub const fn raw(&self) -> u32 {
+        self.0
+    }
+
+    /// The 24-bit address without the flag.
+    pub const fn address(&self) -> u32 {
+        self.0 & 0xffffff
+    }
+
+    /// Whether this is a non-ICAO address, shown with a `~` prefix.
+    pub const fn is_non_icao(&self) -> bool {
+        self.0 & NON_ICAO != 0
+    }
+}
+
+impl fmt::Display for Icao {
+    /// Six lowercase hex digits, `~`-prefixed for non-ICAO addresses, the
+    /// way readsb writes `hex`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_non_icao() {
+            write!(f, "~")?;
+        }
+
+        write!(f, "{:06x}", self.address())
+    }
+}
+
+impl FromStr for Icao {
+    type Err = Error;
+
+    /// Parses six hex digits in either case, optionally `~`-prefixed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (flag, digits) = match s.strip_prefix('~') {
+            Some(digits) => (NON_ICAO, digits),
+            None => (0, s),
+        };
+
+        if digits.len() != 6 || !digits.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Error::InvalidIcao(s.to_string()));
+        }
+
+        let address = u32::from_str_radix(digits, 16).map_err(|_| Error::InvalidIcao(s.to_string()))?;
+
+        Ok(Icao(flag | address))
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use crate::aircraft::Aircraft;
+use crate::bincraft::BinCraft;
+use crate::geo::{BoundingBox, EARTH_RADIUS_NM};
+
+/// Cell size of the index grid in degrees.
+const CELL_DEG: f64 = 1.0;
+
+/// Cells per row of latitude.
+const COLUMNS: i32 = (360.0 / CELL_DEG) as i32;
+
+/// Farthest any two points can be apart, half the Earth's circumference.
+const MAX_DISTANCE_NM: f64 = std::f64::consts::PI * EARTH_RADIUS_NM;
+
+/// A grid of the positioned aircraft of one frame, from
+/// [`BinCraft::build_index`], for answering many proximity queries against
+/// the same frame without scanning every aircraft each time.
+///
+/// Queries only visit the one-degree cells that can hold a match, so their
+/// cost follows the number of aircraft nearby rather than in the frame.
+/// Aircraft without a position are not indexed.
+#[derive(Debug)]
+pub struct SpatialIndex<'a> {
+    aircraft: &'a [Aircraft],
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+fn cell_of(lat: f64, lon: f64) -> (i32, i32) {
+    let row = ((lat + 90.0) / CELL_DEG).floor() as i32;
+    let column = ((lon + 180.0) / CELL_DEG).floor() as i32;
+
+    (row, column.rem_euclid(COLUMNS))
+}
+
+impl BinCraft {
+    /// Indexes the positioned aircraft for repeated queries; see
+    /// [`SpatialIndex`]. The index borrows the frame.
+    pub fn build_index(&self) -> SpatialIndex<'_> {
+        let mut cells = HashMap::<_, Vec<_>>::new();
+
+        for (i, aircraft) in self.aircraft.iter().enumerate() {
+            if let Some((lat, lon)) = aircraft.coordinates() {
+                cells.entry(cell_of(lat, lon)).or_default().push(i);
+            }
+        }
+
+        SpatialIndex { aircraft: &self.aircraft, cells }
+    }
+}
+
+impl<'a> SpatialIndex<'a> {
+    /// Indices of the aircraft in the cells between the given latitudes, over
+    /// `width` degrees of longitude eastwards from `west`, wrapping past
+    /// 180°, or over every longitude.
+    fn candidates(&self, rows: (f64, f64), columns: Option<(f64, f64)>) -> impl Iterator<Item = usize> + '_ {
+        let (south, _) = cell_of(rows.0.max(-90.0), 0.0);
+        let (north, _) = cell_of(rows.1.min(90.0), 0.0);
+
+        let columns = columns
+            .map(|(west, width)| {
+                let first = ((west + 180.0) / CELL_DEG).floor() as i32;
+                let last = ((west + 180.0 + width) / CELL_DEG).floor() as i32;
+
+                (first, last.min(first + COLUMNS - 1))
+            })
+            .unwrap_or((0, COLUMNS - 1));
+
+        (south..=north)
+            .flat_map(move |row| (columns.0..=columns.1).map(move |column| (row, column.rem_euclid(COLUMNS))))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+    }
+
+    /// Aircraft inside `bbox`, which may cross the antimeridian, in frame
+    /// order.
+    pub fn in_bbox(&self, bbox: &BoundingBox) -> Vec<&'a Aircraft> {
+        let width = if bbox.crosses_antimeridian() { bbox.east - bbox.west + 360.0 } else { bbox.east - bbox.west };
+
+        let mut found = self.candidates((bbox.south, bbox.north), Some((bbox.west, width)))
+            .filter(|&i| {
+                let aircraft = &self.aircraft[i];
+
+                aircraft.lat.zip(aircraft.lon).is_some_and(|(lat, lon)| bbox.contains(lat as f64, lon as f64))
+            })
+            .collect::<Vec<_>>();
+
+        found.sort_unstable();
+
+        found.into_iter().map(|i| &self.aircraft[i]).collect()
+    }
+
+    /// Aircraft within `radius_nm` great-circle distance of the point,
+    /// nearest first.
+    pub fn within_radius(&self, lat: f64, lon: f64, radius_nm: f64) -> Vec<&'a Aircraft> {
+        let radius_deg = (radius_nm / EARTH_RADIUS_NM).to_degrees();
+
+        // The widest longitude span of the circle, unless it reaches a pole
+        // or around the globe, in which case every column is visited.
+        let columns = if lat.abs() + radius_deg < 90.0 && radius_deg < 90.0 {
+            let span = (radius_deg.to_radians().sin() / lat.to_radians().cos()).asin().to_degrees();
+
+            Some((lon - span, 2.0 * span)).filter(|_| span < 180.0)
+        } else {
+            None
+        };
+
+        let mut found = self.candidates((lat - radius_deg, lat + radius_deg), columns)
+            .map(|i| &self.aircraft[i])
+            .filter_map(|a| a.distance_from(lat, lon).map(|d| (d, a)))
+            .filter(|(d, _)| *d <= radius_nm)
+            .collect::<Vec<_>>();
+
+        found.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        found.into_iter().map(|(_, a)| a).collect()
+    }
+
+    /// Up to `n` aircraft closest to the point, nearest first, as
+    /// [`BinCraft::nearest`] returns them.
+    pub fn nearest(&self, lat: f64, lon: f64, n: usize) -> Vec<&'a Aircraft> {
+        let indexed = self.cells.values().map(Vec::len).sum::<usize>();
+
+        // Widen the search until it holds `n` aircraft: none outside the
+        // radius can then be closer than those inside.
+        let mut radius_nm = 60.0 * CELL_DEG;
+
+        loop {
+            let mut found = self.within_radius(lat, lon, radius_nm);
+
+            if found.len() >= n.min(indexed) || radius_nm >= MAX_DISTANCE_NM {
+                found.truncate(n);
+
+                return found;
+            }
+
+            radius_nm *= 2.0;
+        }
+    }
+}
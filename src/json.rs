@@ -0,0 +1,408 @@
+use std::io::{self, Write};
+
+use serde::{Deserialize, Deserializer};
+use serde_json::{json, Map, Value};
+
+use crate::aircraft::{AirGround, Aircraft, SignalType, PLAUSIBLE_QNH_HPA};
+use crate::bincraft::BinCraft;
+use crate::category::Category;
+use crate::squawk::Squawk;
+
+/// Fields readsb attributes to the position source in its `mlat`/`tisb`
+/// arrays.
+const SOURCED_FIELDS: [&str; 7] = ["lat", "lon", "nic", "rc", "track", "gs", "baro_rate"];
+
+/// Every nav mode name readsb emits, so parsed names can be stored as
+/// `&'static str` like decoded ones.
+const NAV_MODES: [&str; 6] = ["autopilot", "vnav", "alt_hold", "approach", "lnav", "tcas"];
+
+/// Signal types in the order of their binCraft codes.
+const SIGNAL_TYPES: [SignalType; 14] = [
+    SignalType::AdsbIcao,
+    SignalType::AdsbIcaoNt,
+    SignalType::AdsrIcao,
+    SignalType::TisbIcao,
+    SignalType::Adsc,
+    SignalType::Mlat,
+    SignalType::Other,
+    SignalType::ModeS,
+    SignalType::AdsbOther,
+    SignalType::AdsrOther,
+    SignalType::TisbTrackfile,
+    SignalType::TisbOther,
+    SignalType::ModeAc,
+    SignalType::Unknown,
+];
+
+fn emergency_str(emergency: u8) -> &'static str {
+    match emergency {
+        0 => "none",
+        1 => "general",
+        2 => "lifeguard",
+        3 => "minfuel",
+        4 => "nordo",
+        5 => "unlawful",
+        6 => "downed",
+        _ => "reserved",
+    }
+}
+
+fn sil_type_str(sil_type: u8) -> &'static str {
+    match sil_type {
+        1 => "perhour",
+        2 => "persample",
+        _ => "unknown",
+    }
+}
+
+fn emergency_from_str(emergency: &str) -> Option<u8> {
+    (0..=7).find(|code| emergency_str(*code) == emergency)
+}
+
+fn sil_type_from_str(sil_type: &str) -> u8 {
+    (1..=2).find(|code| sil_type_str(*code) == sil_type).unwrap_or(0)
+}
+
+/// How aircraft are written as JSON.
+#[derive(Clone, Copy, Debug)]
+pub struct JsonOptions {
+    /// Decimal places `lat` and `lon` are rounded to. binCraft positions
+    /// are microdegrees, so 6 keeps them exact.
+    pub coordinate_decimals: u32,
+}
+
+impl Default for JsonOptions {
+    /// Five decimal places, about a meter, well within the accuracy of any
+    /// reported position.
+    fn default() -> Self {
+        JsonOptions {
+            coordinate_decimals: 5,
+        }
+    }
+}
+
+impl JsonOptions {
+    /// Rounds through `f64`, which avoids printing the `f32` value's
+    /// binary expansion such as `51.50000381469727`.
+    fn coordinate(&self, value: f32) -> f64 {
+        let scale = 10f64.powi(self.coordinate_decimals as i32);
+
+        (value as f64 * scale).round() / scale
+    }
+}
+
+impl Aircraft {
+    /// The aircraft as one entry of the readsb/tar1090 `aircraft.json`
+    /// `aircraft` array, with the default [`JsonOptions`]. Missing fields
+    /// are omitted, as readsb does.
+    pub fn to_json(&self) -> Value {
+        self.to_json_with(&JsonOptions::default())
+    }
+
+    /// Like [`Aircraft::to_json`], written according to `options`.
+    pub fn to_json_with(&self, options: &JsonOptions) -> Value {
+        let mut map = Map::new();
+
+        macro_rules! put {
+            ($key:expr, $value:expr) => {
+                if let Some(value) = $value {
+                    map.insert($key.to_string(), json!(value));
+                }
+            };
+        }
+
+        map.insert("hex".to_string(), json!(self.hex));
+
+        if let Some(signal_type) = &self.signal_type {
+            map.insert("type".to_string(), json!(signal_type.as_str()));
+        }
+
+        put!("flight", &self.flight);
+
+        if !self.registration.is_empty() {
+            map.insert("r".to_string(), json!(self.registration));
+        }
+        if !self.tail.is_empty() {
+            map.insert("t".to_string(), json!(self.tail));
+        }
+
+        match self.alt_baro_label {
+            Some(label) => { map.insert("alt_baro".to_string(), json!(label)); }
+            None => put!("alt_baro", self.alt_baro),
+        }
+
+        put!("alt_geom", self.alt_geom);
+        put!("gs", self.gs);
+        put!("ias", self.ias);
+        put!("tas", self.tas);
+        put!("mach", self.mach);
+        put!("wd", self.wd);
+        put!("ws", self.ws);
+        put!("oat", self.oat);
+        put!("tat", self.tat);
+        put!("track", self.track);
+        put!("track_rate", self.track_rate);
+        put!("roll", self.roll);
+        put!("mag_heading", self.mag_heading);
+        put!("true_heading", self.true_heading);
+        put!("baro_rate", self.baro_rate);
+        put!("geom_rate", self.geom_rate);
+        put!("squawk", self.squawk.map(|squawk| squawk.to_string()));
+        put!("emergency", self.emergency.map(emergency_str));
+        put!("category", self.category.map(|category| category.to_string()));
+        put!("nav_qnh", self.nav_qnh);
+        put!("nav_altitude_mcp", self.nav_altitude_mcp);
+        put!("nav_altitude_fms", self.nav_altitude_fms);
+        put!("nav_heading", self.nav_heading);
+
+        if !self.nav_modes.is_empty() {
+            map.insert("nav_modes".to_string(), json!(self.nav_modes));
+        }
+
+        put!("lat", self.lat.map(|lat| options.coordinate(lat)));
+        put!("lon", self.lon.map(|lon| options.coordinate(lon)));
+
+        let quality = &self.quality;
+
+        map.insert("nic".to_string(), json!(quality.nic));
+        map.insert("rc".to_string(), json!(quality.rc));
+
+        put!("seen_pos", self.seen_pos);
+
+        map.insert("version".to_string(), json!(quality.adsb_version));
+
+        put!("nic_baro", quality.nic_baro.map(u8::from));
+        put!("nac_p", quality.nac_p);
+        put!("nac_v", quality.nac_v);
+        put!("sil", quality.sil);
+        map.insert("sil_type".to_string(), json!(sil_type_str(quality.sil_type)));
+        put!("gva", quality.gva);
+        put!("sda", quality.sda);
+        put!("alert", self.alert1.map(u8::from));
+        put!("spi", self.spi.map(u8::from));
+
+        let sourced = || {
+            SOURCED_FIELDS
+                .iter()
+                .filter(|field| map.contains_key(**field))
+                .map(|field| json!(field))
+                .collect::<Vec<_>>()
+        };
+
+        let (mlat, tisb) = match &self.signal_type {
+            Some(SignalType::Mlat) => (sourced(), Vec::new()),
+            Some(signal_type) if signal_type.is_tisb() => (Vec::new(), sourced()),
+            _ => (Vec::new(), Vec::new()),
+        };
+
+        map.insert("mlat".to_string(), Value::Array(mlat));
+        map.insert("tisb".to_string(), Value::Array(tisb));
+
+        put!("messages", self.messages);
+        // Globe tiles carry a rate in place of the count; tar1090 calls it
+        // messageRate.
+        put!("messageRate", self.message_rate);
+        put!("seen", self.seen);
+        map.insert("rssi".to_string(), json!(self.rssi));
+
+        if self.db_flags != 0 {
+            map.insert("dbFlags".to_string(), json!(self.db_flags));
+        }
+
+        Value::Object(map)
+    }
+}
+
+impl BinCraft {
+    /// The frame in the readsb/tar1090 `aircraft.json` shape, so it can be
+    /// served to existing frontends.
+    pub fn to_aircraft_json(&self) -> Value {
+        self.to_aircraft_json_with(&JsonOptions::default())
+    }
+
+    /// Like [`BinCraft::to_aircraft_json`], written according to `options`.
+    pub fn to_aircraft_json_with(&self, options: &JsonOptions) -> Value {
+        json!({
+            "now": self.now,
+            "messages": self.messages,
+            "aircraft": self.aircraft.iter().map(|a| a.to_json_with(options)).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Writes the frame as JSON lines: one compact [`Aircraft::to_json`]
+    /// object per line, each with the frame's `now` added, for `jq` and
+    /// other line-oriented consumers.
+    pub fn write_ndjson<W: Write>(&self, w: W) -> io::Result<()> {
+        self.write_ndjson_with(w, &JsonOptions::default())
+    }
+
+    /// Like [`BinCraft::write_ndjson`], written according to `options`.
+    pub fn write_ndjson_with<W: Write>(&self, mut w: W, options: &JsonOptions) -> io::Result<()> {
+        for aircraft in &self.aircraft {
+            let mut line = aircraft.to_json_with(options);
+            line["now"] = json!(self.now);
+
+            serde_json::to_writer(&mut w, &line)?;
+            w.write_all(b"\n")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// `alt_baro` is either a number of feet or the string `"ground"`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AltBaro {
+    Feet(i32),
+    Label(String),
+}
+
+/// One entry of an `aircraft.json` `aircraft` array. Absent and `null`
+/// fields both become `None`.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct JsonAircraft {
+    hex: String,
+    #[serde(rename = "type")]
+    signal_type: Option<String>,
+    flight: Option<String>,
+    r: Option<String>,
+    t: Option<String>,
+    alt_baro: Option<AltBaro>,
+    alt_geom: Option<i32>,
+    gs: Option<f32>,
+    ias: Option<u16>,
+    tas: Option<u16>,
+    mach: Option<f32>,
+    wd: Option<i16>,
+    ws: Option<i16>,
+    oat: Option<i16>,
+    tat: Option<i16>,
+    track: Option<f32>,
+    track_rate: Option<f32>,
+    roll: Option<f32>,
+    mag_heading: Option<f32>,
+    true_heading: Option<f32>,
+    baro_rate: Option<i32>,
+    geom_rate: Option<i32>,
+    squawk: Option<String>,
+    emergency: Option<String>,
+    category: Option<String>,
+    nav_qnh: Option<f32>,
+    nav_altitude_mcp: Option<u32>,
+    nav_altitude_fms: Option<u32>,
+    nav_heading: Option<f32>,
+    nav_modes: Vec<String>,
+    lat: Option<f32>,
+    lon: Option<f32>,
+    nic: Option<u8>,
+    rc: Option<u16>,
+    seen_pos: Option<f32>,
+    version: Option<u8>,
+    nic_baro: Option<u8>,
+    nac_p: Option<u8>,
+    nac_v: Option<u8>,
+    sil: Option<u8>,
+    sil_type: Option<String>,
+    gva: Option<u8>,
+    sda: Option<u8>,
+    alert: Option<u8>,
+    spi: Option<u8>,
+    messages: Option<u16>,
+    #[serde(rename = "messageRate")]
+    message_rate: Option<f32>,
+    seen: Option<f32>,
+    rssi: Option<f64>,
+    #[serde(rename = "dbFlags")]
+    db_flags: Option<u16>,
+}
+
+impl From<JsonAircraft> for Aircraft {
+    fn from(json: JsonAircraft) -> Self {
+        let mut aircraft = Aircraft::new(json.hex);
+
+        aircraft.signal_type = json.signal_type
+            .and_then(|name| SIGNAL_TYPES.into_iter().find(|signal_type| signal_type.as_str() == name));
+
+        aircraft.flight = json.flight.map(|flight| flight.trim().to_string()).filter(|flight| !flight.is_empty());
+        aircraft.registration = json.r.unwrap_or_default();
+        aircraft.tail = json.t.unwrap_or_default();
+
+        match json.alt_baro {
+            Some(AltBaro::Feet(feet)) => {
+                aircraft.alt_baro = Some(feet);
+                aircraft.airground = AirGround::Airborne;
+            }
+            Some(AltBaro::Label(label)) if label == "ground" => {
+                aircraft.alt_baro_label = Some("ground");
+                aircraft.airground = AirGround::Ground;
+            }
+            _ => {}
+        }
+
+        aircraft.alt_geom = json.alt_geom;
+        aircraft.gs = json.gs;
+        aircraft.ias = json.ias;
+        aircraft.tas = json.tas;
+        aircraft.mach = json.mach;
+        aircraft.wd = json.wd;
+        aircraft.ws = json.ws;
+        aircraft.oat = json.oat;
+        aircraft.tat = json.tat;
+        aircraft.track = json.track;
+        aircraft.track_rate = json.track_rate;
+        aircraft.roll = json.roll;
+        aircraft.mag_heading = json.mag_heading;
+        aircraft.true_heading = json.true_heading;
+        aircraft.baro_rate = json.baro_rate;
+        aircraft.geom_rate = json.geom_rate;
+        aircraft.squawk = json.squawk.and_then(|squawk| squawk.parse::<Squawk>().ok());
+        aircraft.emergency = json.emergency.as_deref().and_then(emergency_from_str);
+        aircraft.category = json.category
+            .and_then(|category| u8::from_str_radix(&category, 16).ok())
+            .map(Category::from_raw);
+        aircraft.nav_qnh = json.nav_qnh.filter(|qnh| PLAUSIBLE_QNH_HPA.contains(qnh));
+        aircraft.nav_altitude_mcp = json.nav_altitude_mcp;
+        aircraft.nav_altitude_fms = json.nav_altitude_fms;
+        aircraft.nav_heading = json.nav_heading;
+        aircraft.nav_modes = json.nav_modes
+            .iter()
+            .filter_map(|mode| NAV_MODES.into_iter().find(|known| known == mode))
+            .collect();
+        aircraft.lat = json.lat;
+        aircraft.lon = json.lon;
+        aircraft.seen_pos = json.seen_pos;
+        aircraft.alert1 = json.alert.map(|alert| alert != 0);
+        aircraft.spi = json.spi.map(|spi| spi != 0);
+        aircraft.messages = json.messages;
+        aircraft.message_rate = json.message_rate;
+        aircraft.seen = json.seen;
+        aircraft.rssi = json.rssi.unwrap_or_default();
+        aircraft.db_flags = json.db_flags.unwrap_or_default();
+
+        let quality = &mut aircraft.quality;
+
+        quality.nic = json.nic.unwrap_or_default();
+        quality.rc = json.rc.unwrap_or_default();
+        quality.adsb_version = json.version.unwrap_or_default();
+        quality.nic_baro = json.nic_baro.map(|nic_baro| nic_baro != 0);
+        quality.nac_p = json.nac_p;
+        quality.nac_v = json.nac_v;
+        quality.sil = json.sil;
+        quality.sil_type = json.sil_type.as_deref().map(sil_type_from_str).unwrap_or_default();
+        quality.gva = json.gva;
+        quality.sda = json.sda;
+
+        aircraft
+    }
+}
+
+impl<'de> Deserialize<'de> for Aircraft {
+    /// Reads one entry of a readsb/tar1090 `aircraft.json` `aircraft` array,
+    /// the inverse of [`Aircraft::to_json`]. Fields that JSON does not carry,
+    /// such as `nogps` or the receiver id, are left at their defaults.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        JsonAircraft::deserialize(deserializer).map(Aircraft::from)
+    }
+}
@@ -0,0 +1,108 @@
+use crate::aircraft::Aircraft;
+use crate::bincraft::BinCraft;
+
+const FEET_TO_METRES: f64 = 0.3048;
+
+/// `text` with the characters XML and HTML reserve replaced by entities.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// The key fields of `aircraft` as HTML, one per line of the balloon Google
+/// Earth shows for it.
+fn description(aircraft: &Aircraft) -> String {
+    let mut lines = vec![format!("Hex: {}", escape(&aircraft.hex))];
+
+    if !aircraft.registration.is_empty() {
+        lines.push(format!("Registration: {}", escape(&aircraft.registration)));
+    }
+
+    if aircraft.is_on_ground() {
+        lines.push("Altitude: ground".to_string());
+    } else if let Some(alt) = aircraft.alt_baro {
+        lines.push(format!("Altitude: {} ft", alt));
+    }
+
+    if let Some(gs) = aircraft.gs {
+        lines.push(format!("Ground speed: {:.0} kt", gs));
+    }
+
+    if let Some(track) = aircraft.track {
+        lines.push(format!("Track: {:.0}°", track));
+    }
+
+    if let Some(squawk) = aircraft.squawk {
+        lines.push(format!("Squawk: {}", squawk));
+    }
+
+    if let Some(signal_type) = aircraft.signal_type {
+        lines.push(format!("Source: {}", signal_type.as_str()));
+    }
+
+    lines.join("<br/>")
+}
+
+/// One `Placemark` for `aircraft`, or `None` without a position.
+fn placemark(aircraft: &Aircraft) -> Option<String> {
+    let (lat, lon) = aircraft.coordinates()?;
+    let name = aircraft.flight.as_deref().filter(|flight| !flight.is_empty()).unwrap_or(&aircraft.hex);
+
+    // Absolute altitudes are above sea level, closer to the geometric
+    // altitude than to the pressure altitude, which is kept as a fallback.
+    let altitude_m = aircraft.alt_geom.or(aircraft.alt_baro).map(|feet| feet as f64 * FEET_TO_METRES);
+
+    // Positions are stored in microdegrees, so six decimals lose nothing.
+    let point = match altitude_m.filter(|_| !aircraft.is_on_ground()) {
+        Some(altitude_m) => format!(
+            "<extrude>1</extrude><altitudeMode>absolute</altitudeMode><coordinates>{:.6},{:.6},{:.0}</coordinates>",
+            lon, lat, altitude_m,
+        ),
+        None => format!("<altitudeMode>clampToGround</altitudeMode><coordinates>{:.6},{:.6}</coordinates>", lon, lat),
+    };
+
+    Some(format!(
+        "<Placemark><name>{}</name><description>{}</description><Point>{}</Point></Placemark>\n",
+        escape(name),
+        escape(&description(aircraft)),
+        point,
+    ))
+}
+
+impl BinCraft {
+    /// The frame as a KML document for Google Earth, with a placemark per
+    /// positioned aircraft named after its callsign, or hex without one.
+    ///
+    /// Airborne aircraft are placed at their altitude above sea level,
+    /// geometric where known and barometric otherwise, with a line
+    /// extruded down to the ground; aircraft on the ground or without an
+    /// altitude are clamped to the terrain. Each placemark's description
+    /// lists the hex, registration, altitude, ground speed, track, squawk
+    /// and source that are known.
+    pub fn to_kml(&self) -> String {
+        let mut kml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <kml xmlns=\"http://www.opengis.net/kml/2.2\">\n<Document>\n",
+        );
+
+        for placemark in self.aircraft.iter().filter_map(placemark) {
+            kml.push_str(&placemark);
+        }
+
+        kml.push_str("</Document>\n</kml>\n");
+
+        kml
+    }
+}
@@ -0,0 +1,107 @@
+//! Decoder for the binCraft format served by adsbexchange and other readsb
+//! / tar1090 instances, with optional fetching from the re-api.
+//!
+//! The core (parsing with [`parse_adsb`], [`parse_reader`] and
+//! [`parse_file`], zstd decompression, and the filtering and geometry
+//! helpers) needs no optional features. Everything else is additive:
+//!
+//! | Feature     | Default | Adds                                                  |
+//! |-------------|---------|-------------------------------------------------------|
+//! | `net`       | yes     | `Client`, the `fetch_*` functions and `stream_box` (reqwest, tokio) |
+//! | `stream`    | via `net` | `replay` of archived dumps and `stream_source` polling as async streams |
+//! | `blocking`  |         | `fetch_*_blocking` for code without an async runtime; implies `net` |
+//! | `serde`     |         | JSON import/export and readsb trace files             |
+//! | `gzip`      |         | gzip input in `parse_reader`/`parse_file`, and `BinCraft::write_gzip` |
+//! | `brotli`    |         | brotli input in `parse_file`                          |
+//! | `metrics`   |         | Prometheus metrics; implies `net`                     |
+//! | `tracing`   |         | spans and events around fetching and parsing          |
+//! | `raw-bytes` |         | each aircraft's undecoded record                      |
+//! | `test-util` |         | `testutil`, reproducible frames for tests             |
+//!
+//! For offline use, such as parsing local dumps or targeting wasm32, depend
+//! on the crate with `default-features = false`.
+
+mod aircraft;
+mod alert;
+mod bincraft;
+mod category;
+mod coverage;
+mod decimate;
+mod decompress;
+mod diff;
+mod error;
+#[cfg(feature = "net")]
+mod fetch;
+mod fields;
+mod geo;
+mod globe;
+mod icao;
+mod index;
+#[cfg(feature = "serde")]
+mod json;
+mod kml;
+mod merge;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "stream")]
+mod poll;
+mod query;
+mod rate;
+mod read;
+mod report;
+#[cfg(feature = "stream")]
+mod replay;
+pub mod scale;
+mod smooth;
+mod source;
+mod squawk;
+#[cfg(feature = "test-util")]
+pub mod testutil;
+#[cfg(feature = "serde")]
+mod trace;
+mod warning;
+mod write;
+
+pub use aircraft::{Activity, AirGround, Aircraft, Altitude, Integrity, PositionQuality, PositionSource, QualityMetrics, SignalClass, SignalType, SpeedKind, VerticalTrend, Wind};
+pub use alert::AlertTiers;
+pub use bincraft::{decode_record, iter_aircraft, iter_frames, parse_adsb, parse_adsb_with, parse_header, BinCraft, BinCraftHeader, ParseContext};
+pub use category::Category;
+pub use coverage::{CoverageSector, COVERAGE_SECTORS};
+pub use decimate::DecimateStrategy;
+pub use decompress::{decompress, decompress_with_limit, DEFAULT_MAX_DECOMPRESSED_SIZE};
+pub use diff::{Diff, DiffThreshold};
+pub use error::Error;
+#[cfg(feature = "net")]
+pub use fetch::{fetch_all_visible, fetch_box, fetch_box_split, fetch_boxes, fetch_by_callsign, fetch_by_registration, fetch_hex, fetch_query, Client, Config, DEFAULT_BASE_URL, MAX_CACHED_RESPONSES};
+#[cfg(feature = "blocking")]
+pub use fetch::{fetch_box_blocking, fetch_hex_blocking, fetch_query_blocking};
+#[cfg(all(feature = "net", feature = "serde"))]
+pub use fetch::fetch_trace;
+pub use fields::FieldGroups;
+pub use geo::BoundingBox;
+pub use globe::GlobeIndex;
+pub use icao::Icao;
+pub use index::SpatialIndex;
+pub use merge::MergePolicy;
+#[cfg(feature = "serde")]
+pub use json::JsonOptions;
+#[cfg(feature = "metrics")]
+pub use metrics::{serve as serve_metrics, Metrics};
+#[cfg(feature = "net")]
+pub use poll::stream_box;
+#[cfg(feature = "stream")]
+pub use poll::{stream_source, PollInterval, StreamEvent};
+pub use query::{Compression, DbFlag, QueryBuilder};
+pub use rate::{MessageRateTracker, MessageStats};
+pub use read::{parse_file, parse_file_with_limit, parse_reader, parse_reader_with_limit};
+pub use report::PositionReport;
+#[cfg(feature = "stream")]
+pub use replay::replay;
+pub use smooth::PositionSmoother;
+#[cfg(feature = "net")]
+pub use source::BoxSource;
+pub use source::{AircraftSource, FileSource, Fixture};
+pub use squawk::Squawk;
+#[cfg(feature = "serde")]
+pub use trace::{parse_trace, parse_trace_with_limit, Trace, TracePoint};
+pub use warning::Warning;
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use crate::aircraft::{Aircraft, SignalClass};
+use crate::bincraft::BinCraft;
+
+/// How to choose between reports of the same hex when merging frames.
+#[derive(Clone, Debug)]
+pub struct MergePolicy {
+    /// Signal classes from most to least preferred. Classes not listed, and
+    /// aircraft without a signal type, rank below every listed class.
+    pub preference: Vec<SignalClass>,
+}
+
+impl MergePolicy {
+    /// ADS-B over ADS-R over TIS-B over MLAT over everything else.
+    pub const DEFAULT_PREFERENCE: [SignalClass; 5] = [
+        SignalClass::Adsb,
+        SignalClass::Adsr,
+        SignalClass::Tisb,
+        SignalClass::Mlat,
+        SignalClass::Other,
+    ];
+
+    fn rank(&self, aircraft: &Aircraft) -> usize {
+        aircraft.signal_type
+            .as_ref()
+            .and_then(|signal_type| {
+                self.preference
+                    .iter()
+                    .position(|class| *class == signal_type.class())
+            })
+            .unwrap_or(self.preference.len())
+    }
+}
+
+impl Default for MergePolicy {
+    fn default() -> Self {
+        MergePolicy {
+            preference: Self::DEFAULT_PREFERENCE.to_vec(),
+        }
+    }
+}
+
+/// When the aircraft was last heard, in frame time: the time of its last
+/// position, then of its last message. Later is greater; unknown is least.
+fn last_heard(now: f64, aircraft: &Aircraft) -> (Option<f64>, Option<f64>) {
+    let at = |seen: Option<f32>| seen.map(|seen| now - seen as f64);
+
+    (at(aircraft.seen_pos), at(aircraft.seen))
+}
+
+/// Shifts the aircraft's ages from `from` to the later frame time `to`.
+fn rebase(aircraft: &mut Aircraft, from: f64, to: f64) {
+    let shift = (to - from) as f32;
+
+    if shift > 0.0 {
+        aircraft.seen = aircraft.seen.map(|seen| seen + shift);
+        aircraft.seen_pos = aircraft.seen_pos.map(|seen| seen + shift);
+    }
+}
+
+impl BinCraft {
+    /// Merges the aircraft of another frame into this one, keeping a single
+    /// report per hex. When both frames contain a hex, the other frame's
+    /// report replaces this one only if its signal class ranks strictly
+    /// higher under `policy`.
+    ///
+    /// Header fields are kept from `self`, except `now` and `fetched_at`,
+    /// which become the later of the two, and `warnings`, which are
+    /// combined; every `seen` and `seen_pos` is shifted to stay relative to
+    /// `now`.
+    pub fn merge(&mut self, other: BinCraft, policy: &MergePolicy) {
+        self.merge_by(other, |_, new, old| policy.rank(new) < policy.rank(old));
+    }
+
+    /// Merges the aircraft of another frame into this one, keeping a single
+    /// report per hex: whichever was heard last, going by the time of the
+    /// last position and then of the last message. This reconciles the
+    /// duplicates that overlapping boxes return near their shared edges.
+    ///
+    /// Header fields are kept from `self`, except `now` and `fetched_at`,
+    /// which become the later of the two, and `warnings`, which are
+    /// combined; every `seen` and `seen_pos` is shifted to stay relative to
+    /// `now`.
+    pub fn merge_newest(&mut self, other: BinCraft) {
+        self.merge_by(other, |now, new, old| last_heard(now, new) > last_heard(now, old));
+    }
+
+    /// Merges `other` into this frame as [`BinCraft::merge`] describes,
+    /// with `replaces(now, new, old)` deciding whether a report from
+    /// `other` takes the place of one already here for the same hex. Both
+    /// reports have been shifted to the merged `now` by then.
+    fn merge_by(&mut self, other: BinCraft, replaces: impl Fn(f64, &Aircraft, &Aircraft) -> bool) {
+        let now = self.now.max(other.now);
+        self.fetched_at = self.fetched_at.max(other.fetched_at);
+        self.warnings.extend(other.warnings);
+
+        for aircraft in &mut self.aircraft {
+            rebase(aircraft, self.now, now);
+        }
+
+        let mut index = self.aircraft
+            .iter()
+            .enumerate()
+            .map(|(i, a)| (a.hex.clone(), i))
+            .collect::<HashMap<_, _>>();
+
+        for mut aircraft in other.aircraft {
+            rebase(&mut aircraft, other.now, now);
+
+            match index.get(&aircraft.hex) {
+                Some(&i) => {
+                    if replaces(now, &aircraft, &self.aircraft[i]) {
+                        self.aircraft[i] = aircraft;
+                    }
+                }
+                None => {
+                    index.insert(aircraft.hex.clone(), self.aircraft.len());
+                    self.aircraft.push(aircraft);
+                }
+            }
+        }
+
+        self.now = now;
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, ToSocketAddrs};
+
+use crate::bincraft::BinCraft;
+use crate::error::Error;
+use crate::rate::MessageRateTracker;
+
+#[derive(Debug, Default)]
+struct State {
+    aircraft: usize,
+    positioned: usize,
+    global_positioned: u32,
+    message_rate: MessageRateTracker,
+    fetch_latency: Duration,
+    fetches: u64,
+    fetch_errors: u64,
+    decompress_errors: u64,
+}
+
+/// Counters and gauges for a long-running collector, rendered in the
+/// Prometheus text exposition format.
+///
+/// Attach to a [`Client`](crate::Client) with
+/// [`Client::with_metrics`](crate::Client::with_metrics) to record every
+/// fetch, and expose with [`serve`].
+#[derive(Debug, Default)]
+pub struct Metrics {
+    state: Mutex<State>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Metrics::default()
+    }
+
+    /// Records a parsed frame. The message rate is tracked with a
+    /// [`MessageRateTracker`](crate::MessageRateTracker) and reads as zero
+    /// until it is known.
+    pub fn observe_frame(&self, frame: &BinCraft) {
+        let mut state = self.state.lock().unwrap();
+
+        state.aircraft = frame.aircraft.len();
+        state.positioned = frame.positioned().count();
+        state.global_positioned = frame.global_ac_count_withpos;
+        state.message_rate.update(frame);
+    }
+
+    /// Records the outcome and duration of one fetch.
+    pub fn observe_fetch(&self, result: &Result<BinCraft, Error>, latency: Duration) {
+        {
+            let mut state = self.state.lock().unwrap();
+
+            state.fetches += 1;
+            state.fetch_latency = latency;
+
+            match result {
+                Err(Error::Decompress(_) | Error::TooLarge { .. }) => {
+                    state.fetch_errors += 1;
+                    state.decompress_errors += 1;
+                }
+                Err(_) => state.fetch_errors += 1,
+                Ok(_) => {}
+            }
+        }
+
+        if let Ok(frame) = result {
+            self.observe_frame(frame);
+        }
+    }
+
+    /// The current values in Prometheus text format.
+    pub fn render(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let mut out = String::new();
+
+        let mut metric = |name: &str, kind: &str, help: &str, value: f64| {
+            let _ = writeln!(out, "# HELP adsbexchange_{} {}", name, help);
+            let _ = writeln!(out, "# TYPE adsbexchange_{} {}", name, kind);
+            let _ = writeln!(out, "adsbexchange_{} {}", name, value);
+        };
+
+        metric("aircraft", "gauge", "Aircraft in the last frame.", state.aircraft as f64);
+        metric("aircraft_positioned", "gauge", "Aircraft with a position in the last frame.", state.positioned as f64);
+        metric("global_aircraft_positioned", "gauge", "Aircraft with a position known to the server worldwide.", state.global_positioned as f64);
+        metric("messages_per_second", "gauge", "Messages per second from the header message counter.", state.message_rate.rate().unwrap_or(0.0));
+        metric("fetch_latency_seconds", "gauge", "Duration of the last fetch.", state.fetch_latency.as_secs_f64());
+        metric("fetches_total", "counter", "Fetches attempted.", state.fetches as f64);
+        metric("fetch_errors_total", "counter", "Fetches that failed.", state.fetch_errors as f64);
+        metric("decompress_errors_total", "counter", "Fetches whose body failed to decompress.", state.decompress_errors as f64);
+
+        out
+    }
+}
+
+/// Serves `metrics` over plain HTTP on `addr`, answering every request with
+/// the rendered metrics. Runs until accepting a connection fails.
+pub async fn serve<A: ToSocketAddrs>(metrics: Arc<Metrics>, addr: A) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+
+    loop {
+        let (mut socket, _) = listener.accept().await?;
+        let body = metrics.render();
+
+        tokio::spawn(async move {
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body,
+            );
+
+            let _ = socket.write_all(response.as_bytes()).await;
+        });
+    }
+}
@@ -0,0 +1,223 @@
+use std::time::Duration;
+
+use futures_util::stream::{self, Stream};
+
+use crate::bincraft::BinCraft;
+use crate::error::Error;
+#[cfg(feature = "net")]
+use crate::fetch::Client;
+#[cfg(feature = "net")]
+use crate::geo::BoundingBox;
+#[cfg(feature = "net")]
+use crate::source::BoxSource;
+use crate::source::AircraftSource;
+
+/// Longest wait between retries while the server is unreachable, unless the
+/// poll interval itself is longer.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// An item of [`stream_source`] and `stream_box`.
+#[derive(Debug)]
+pub enum StreamEvent {
+    /// A fetch succeeded, for the first time or after failures. The frame
+    /// follows as the next event.
+    Connected,
+    /// A freshly fetched frame.
+    Frame(BinCraft),
+    /// A fetch failed; the next attempt is made after `retry_in`. Failures
+    /// never produce an empty frame: `last_frame` is the last frame
+    /// yielded before the failure, kept by the stream across the outage so
+    /// a map can go on showing it however long reconnecting takes. It is
+    /// `None` until the first frame arrives, and its ages are as fetched,
+    /// so [`BinCraft::age`] tells how stale it has become.
+    Reconnecting { attempt: u32, retry_in: Duration, error: Error, last_frame: Option<BinCraft> },
+}
+
+/// How long [`stream_source`] and `stream_box` wait between fetches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PollInterval {
+    /// A fixed wait after each frame.
+    Fixed(Duration),
+    /// Follow the server's own update cadence, learned from the change in
+    /// [`BinCraft::now`] between frames: the next fetch is timed for when
+    /// the server should have the next frame ready, within `min..=max` of
+    /// the last new frame. A fetch that returns the same frame again is
+    /// not yielded and is retried after `min`, so `min` bounds how late a
+    /// new frame can be picked up.
+    Adaptive { min: Duration, max: Duration },
+}
+
+impl PollInterval {
+    /// The shortest wait between fetches.
+    fn min(&self) -> Duration {
+        match *self {
+            PollInterval::Fixed(interval) => interval,
+            PollInterval::Adaptive { min, .. } => min,
+        }
+    }
+}
+
+impl From<Duration> for PollInterval {
+    fn from(interval: Duration) -> Self {
+        PollInterval::Fixed(interval)
+    }
+}
+
+struct Poll<S> {
+    source: S,
+    interval: PollInterval,
+    connected: bool,
+    failures: u32,
+    /// A frame fetched while reporting `Connected`, to be yielded next.
+    pending: Option<BinCraft>,
+    /// Wait before the next fetch; `None` for the very first one.
+    delay: Option<Duration>,
+    /// `now` of the last frame yielded, for adaptive polling.
+    last_now: Option<f64>,
+    /// Seconds between the server's last two frames, for adaptive polling.
+    cadence: Option<f64>,
+    /// The last frame yielded, re-yielded with each failure.
+    last_frame: Option<BinCraft>,
+}
+
+impl<S> Poll<S> {
+    /// Doubles the wait with each consecutive failure, starting from the
+    /// poll interval.
+    fn backoff(&self) -> Duration {
+        let factor = 1u32 << (self.failures - 1).min(16);
+        let interval = self.interval.min();
+
+        interval
+            .max(Duration::from_secs(1))
+            .saturating_mul(factor)
+            .min(MAX_BACKOFF.max(interval))
+    }
+
+    /// The wait after a successful fetch of `frame`, or `None` if the frame
+    /// is one already yielded and should be skipped.
+    fn next_delay(&mut self, frame: &BinCraft) -> Option<Duration> {
+        let PollInterval::Adaptive { min, max } = self.interval else {
+            return Some(self.interval.min());
+        };
+
+        match self.last_now {
+            Some(last_now) if frame.now <= last_now => {
+                self.delay = Some(min);
+                return None;
+            }
+            Some(last_now) => self.cadence = Some(frame.now - last_now),
+            None => {}
+        }
+
+        self.last_now = Some(frame.now);
+
+        // `now` comes from the source, which may report any float.
+        let cadence = self.cadence.and_then(|cadence| Duration::try_from_secs_f64(cadence).ok()).unwrap_or(min);
+
+        Some(cadence.clamp(min, max.max(min)))
+    }
+}
+
+/// Polls `source` every `interval`, yielding each frame. A [`Duration`]
+/// polls at a fixed interval; see [`PollInterval::Adaptive`] for following
+/// the server's cadence.
+///
+/// Failed snapshots are reported as [`StreamEvent::Reconnecting`], with the
+/// last frame yielded, and retried with exponential backoff; the stream
+/// itself never ends.
+///
+/// # Cancellation
+///
+/// The stream does all its work inside `poll_next`, without spawning a
+/// task, so dropping it stops polling at once: a fetch in flight is
+/// dropped mid-request and a pending wait is abandoned. To stop on a
+/// signal, such as Ctrl-C or a new viewport, race `next()` against the
+/// signal in `tokio::select!` and drop the stream when the signal wins, or
+/// end it with `StreamExt::take_until` from `futures-util`.
+pub fn stream_source<S: AircraftSource>(
+    source: S,
+    interval: impl Into<PollInterval>,
+) -> impl Stream<Item = StreamEvent> {
+    let poll = Poll {
+        source,
+        interval: interval.into(),
+        connected: false,
+        failures: 0,
+        pending: None,
+        delay: None,
+        last_now: None,
+        cadence: None,
+        last_frame: None,
+    };
+
+    stream::unfold(poll, |mut poll| async move {
+        if let Some(frame) = poll.pending.take() {
+            poll.last_frame = Some(frame.clone());
+
+            return Some((StreamEvent::Frame(frame), poll));
+        }
+
+        loop {
+            if let Some(delay) = poll.delay.take() {
+                tokio::time::sleep(delay).await;
+            }
+
+            match poll.source.snapshot().await {
+                Ok(frame) => {
+                    poll.failures = 0;
+
+                    let Some(delay) = poll.next_delay(&frame) else {
+                        continue;
+                    };
+
+                    poll.delay = Some(delay);
+
+                    if poll.connected {
+                        poll.last_frame = Some(frame.clone());
+
+                        return Some((StreamEvent::Frame(frame), poll));
+                    }
+
+                    poll.connected = true;
+                    poll.pending = Some(frame);
+
+                    return Some((StreamEvent::Connected, poll));
+                }
+                Err(error) => {
+                    poll.connected = false;
+                    poll.failures += 1;
+
+                    let retry_in = poll.backoff();
+                    poll.delay = Some(retry_in);
+
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(attempt = poll.failures, ?retry_in, %error, "poll failed");
+
+                    let event = StreamEvent::Reconnecting {
+                        attempt: poll.failures,
+                        retry_in,
+                        error,
+                        last_frame: poll.last_frame.clone(),
+                    };
+
+                    return Some((event, poll));
+                }
+            }
+        }
+    })
+}
+
+/// Polls `bbox` every `interval`, yielding each frame. A box crossing the
+/// antimeridian is fetched as in
+/// [`fetch_box_split`](crate::fetch_box_split).
+///
+/// The same as [`stream_source`] over a [`BoxSource`], and cancelled the
+/// same way, by dropping the stream.
+#[cfg(feature = "net")]
+pub fn stream_box(
+    client: &Client,
+    bbox: BoundingBox,
+    interval: impl Into<PollInterval>,
+) -> impl Stream<Item = StreamEvent> {
+    stream_source(BoxSource::new(client.clone(), bbox), interval)
+}
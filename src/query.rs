@@ -0,0 +1,246 @@
+use std::fmt::Write;
+
+use crate::geo::BoundingBox;
+use crate::squawk::Squawk;
+
+/// How the server should compress the binCraft body.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// Plain binCraft, for servers built without zstd.
+    None,
+    #[default]
+    Zstd,
+}
+
+/// Database flags the server can filter on, named after readsb's
+/// `filter_dbFlag` values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DbFlag {
+    Military,
+    Interesting,
+    /// Privacy ICAO Address.
+    Pia,
+    /// Limiting Aircraft Data Displayed.
+    Ladd,
+}
+
+impl DbFlag {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DbFlag::Military => "military",
+            DbFlag::Interesting => "interesting",
+            DbFlag::Pia => "pia",
+            DbFlag::Ladd => "ladd",
+        }
+    }
+}
+
+/// What a query selects, the one parameter every re-api request needs.
+#[derive(Clone, Debug, PartialEq)]
+enum Selection {
+    Box(BoundingBox),
+    Circle { lat: f64, lon: f64, radius_nm: f64 },
+    Closest { lat: f64, lon: f64, radius_nm: f64 },
+    Hex(Vec<String>),
+    Callsign(Vec<String>),
+    Registration(Vec<String>),
+    TypeCode(Vec<String>),
+    All,
+    AllWithPos,
+}
+
+/// A re-api request: what to select, how to compress the answer, and any
+/// server-side filters, turned into a URL by [`QueryBuilder::url`].
+///
+/// Start from one of the selections, then narrow it:
+///
+/// ```
+/// use adsbexchange::{BoundingBox, DbFlag, QueryBuilder};
+///
+/// let query = QueryBuilder::bbox(&BoundingBox::new(50.0, 52.0, -1.0, 1.0))
+///     .with_above_alt_baro(10_000)
+///     .with_db_flag(DbFlag::Military);
+///
+/// assert_eq!(
+///     query.query(),
+///     "binCraft&zstd&box=50,52,-1,1&above_alt_baro=10000&filter_dbFlag=military",
+/// );
+/// ```
+///
+/// Parameters this type has no setter for yet can be added with
+/// [`QueryBuilder::with_param`]. Values are sent as given, so they should
+/// not need escaping; callsigns, registrations and type codes never do.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryBuilder {
+    selection: Selection,
+    compression: Compression,
+    db_flags: Vec<DbFlag>,
+    params: Vec<(String, Option<String>)>,
+}
+
+impl QueryBuilder {
+    fn new(selection: Selection) -> Self {
+        QueryBuilder {
+            selection,
+            compression: Compression::default(),
+            db_flags: Vec::new(),
+            params: Vec::new(),
+        }
+    }
+
+    /// The aircraft inside `bbox`. A box crossing the antimeridian is sent
+    /// as is; `fetch_box_split` splits it for servers that do not handle
+    /// it.
+    pub fn bbox(bbox: &BoundingBox) -> Self {
+        QueryBuilder::new(Selection::Box(*bbox))
+    }
+
+    /// The aircraft within `radius_nm` of a point.
+    pub fn circle(lat: f64, lon: f64, radius_nm: f64) -> Self {
+        QueryBuilder::new(Selection::Circle { lat, lon, radius_nm })
+    }
+
+    /// The single aircraft closest to a point, within `radius_nm`.
+    pub fn closest(lat: f64, lon: f64, radius_nm: f64) -> Self {
+        QueryBuilder::new(Selection::Closest { lat, lon, radius_nm })
+    }
+
+    /// The aircraft with the given ICAO hex addresses.
+    pub fn hex(hex: &[&str]) -> Self {
+        QueryBuilder::new(Selection::Hex(to_owned(hex)))
+    }
+
+    /// The aircraft flying under the given callsigns.
+    pub fn callsign(callsigns: &[&str]) -> Self {
+        QueryBuilder::new(Selection::Callsign(to_owned(callsigns)))
+    }
+
+    /// The aircraft with the given registrations.
+    pub fn registration(registrations: &[&str]) -> Self {
+        QueryBuilder::new(Selection::Registration(to_owned(registrations)))
+    }
+
+    /// The aircraft of the given ICAO type codes, such as `A20N`.
+    pub fn type_code(type_codes: &[&str]) -> Self {
+        QueryBuilder::new(Selection::TypeCode(to_owned(type_codes)))
+    }
+
+    /// Every aircraft the server tracks, with or without a position.
+    pub fn all() -> Self {
+        QueryBuilder::new(Selection::All)
+    }
+
+    /// Every aircraft the server tracks with a position.
+    pub fn all_with_pos() -> Self {
+        QueryBuilder::new(Selection::AllWithPos)
+    }
+
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Only aircraft squawking `squawk`.
+    pub fn with_squawk(self, squawk: Squawk) -> Self {
+        self.with_param("filter_squawk", Some(&squawk.to_string()))
+    }
+
+    /// Only aircraft whose callsign is exactly `callsign`.
+    pub fn with_callsign_exact(self, callsign: &str) -> Self {
+        self.with_param("filter_callsign_exact", Some(callsign))
+    }
+
+    /// Only aircraft whose callsign starts with `prefix`, such as an
+    /// airline's ICAO code.
+    pub fn with_callsign_prefix(self, prefix: &str) -> Self {
+        self.with_param("filter_callsign_prefix", Some(prefix))
+    }
+
+    /// Only aircraft of the given ICAO type codes.
+    pub fn with_type_codes(self, type_codes: &[&str]) -> Self {
+        self.with_param("filter_type", Some(&type_codes.join(",")))
+    }
+
+    /// Only aircraft at or above `feet` barometric altitude.
+    pub fn with_above_alt_baro(self, feet: i32) -> Self {
+        self.with_param("above_alt_baro", Some(&feet.to_string()))
+    }
+
+    /// Only aircraft at or below `feet` barometric altitude.
+    pub fn with_below_alt_baro(self, feet: i32) -> Self {
+        self.with_param("below_alt_baro", Some(&feet.to_string()))
+    }
+
+    /// Only aircraft with a position.
+    pub fn with_positioned_only(self) -> Self {
+        self.with_param("filter_with_pos", None)
+    }
+
+    /// Only aircraft carrying `flag` in the server's database. Flags set
+    /// more than once are sent once; aircraft matching any of them are
+    /// returned.
+    pub fn with_db_flag(mut self, flag: DbFlag) -> Self {
+        if !self.db_flags.contains(&flag) {
+            self.db_flags.push(flag);
+        }
+        self
+    }
+
+    /// Appends a parameter, `key=value` or a bare `key`, for server options
+    /// without a setter of their own.
+    pub fn with_param(mut self, key: &str, value: Option<&str>) -> Self {
+        self.params.push((key.to_string(), value.map(str::to_string)));
+        self
+    }
+
+    /// The query string, without the leading `?`.
+    pub fn query(&self) -> String {
+        let mut query = String::from("binCraft");
+
+        if self.compression == Compression::Zstd {
+            query.push_str("&zstd");
+        }
+
+        // Writing to a String cannot fail.
+        let _ = match &self.selection {
+            Selection::Box(bbox) => write!(query, "&box={},{},{},{}", bbox.south, bbox.north, bbox.west, bbox.east),
+            Selection::Circle { lat, lon, radius_nm } => write!(query, "&circle={},{},{}", lat, lon, radius_nm),
+            Selection::Closest { lat, lon, radius_nm } => write!(query, "&closest={},{},{}", lat, lon, radius_nm),
+            Selection::Hex(hex) => write!(query, "&find_hex={}", hex.join(",")),
+            Selection::Callsign(callsigns) => write!(query, "&find_callsign={}", callsigns.join(",")),
+            Selection::Registration(registrations) => write!(query, "&find_reg={}", registrations.join(",")),
+            Selection::TypeCode(type_codes) => write!(query, "&find_type={}", type_codes.join(",")),
+            Selection::All => write!(query, "&all"),
+            Selection::AllWithPos => write!(query, "&all_with_pos"),
+        };
+
+        for (key, value) in &self.params {
+            query.push('&');
+            query.push_str(key);
+
+            if let Some(value) = value {
+                query.push('=');
+                query.push_str(value);
+            }
+        }
+
+        if !self.db_flags.is_empty() {
+            let flags = self.db_flags.iter().map(DbFlag::as_str).collect::<Vec<_>>();
+
+            query.push_str("&filter_dbFlag=");
+            query.push_str(&flags.join(","));
+        }
+
+        query
+    }
+
+    /// The full request URL under `base_url`, the re-api root as in
+    /// `Config::base_url`.
+    pub fn url(&self, base_url: &str) -> String {
+        format!("{}?{}", base_url, self.query())
+    }
+}
+
+fn to_owned(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
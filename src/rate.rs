@@ -0,0 +1,110 @@
+use crate::bincraft::BinCraft;
+
+/// Turns the cumulative `messages` counter of successive frames into a
+/// messages-per-second figure.
+///
+/// The rate is the change in `messages` over the change in `now` between
+/// the two most recent frames. A counter that goes backwards means the
+/// server restarted; the tracker then starts over from the new frame.
+/// Frames whose `now` does not advance leave the rate unchanged.
+#[derive(Clone, Debug, Default)]
+pub struct MessageRateTracker {
+    /// `now` and `messages` of the previous frame.
+    last: Option<(f64, u32)>,
+    rate: Option<f64>,
+}
+
+impl MessageRateTracker {
+    pub fn new() -> Self {
+        MessageRateTracker::default()
+    }
+
+    /// Records `frame` and returns the updated rate, or `None` until two
+    /// frames from the same counter run have been seen.
+    pub fn update(&mut self, frame: &BinCraft) -> Option<f64> {
+        if let Some((now, messages)) = self.last {
+            let elapsed = frame.now - now;
+
+            if frame.messages < messages {
+                self.rate = None;
+            } else if elapsed > 0.0 {
+                self.rate = Some((frame.messages - messages) as f64 / elapsed);
+            }
+        }
+
+        self.last = Some((frame.now, frame.messages));
+
+        self.rate
+    }
+
+    /// The rate as of the last [`update`](Self::update).
+    pub fn rate(&self) -> Option<f64> {
+        self.rate
+    }
+
+    /// Forgets all frames seen so far.
+    pub fn reset(&mut self) {
+        *self = MessageRateTracker::default();
+    }
+}
+
+/// Minimum, maximum and mean of a per-aircraft figure over a frame, from
+/// [`BinCraft::message_count_stats`] and [`BinCraft::message_rate_stats`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MessageStats {
+    /// Aircraft that reported the figure.
+    pub count: usize,
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+}
+
+impl MessageStats {
+    fn of(values: impl Iterator<Item = f32>) -> Option<Self> {
+        let mut stats: Option<MessageStats> = None;
+        let mut sum = 0.0f64;
+
+        for value in values {
+            sum += value as f64;
+
+            let stats = stats.get_or_insert(MessageStats { count: 0, min: value, max: value, mean: 0.0 });
+            stats.count += 1;
+            stats.min = stats.min.min(value);
+            stats.max = stats.max.max(value);
+        }
+
+        stats.map(|stats| MessageStats { mean: (sum / stats.count as f64) as f32, ..stats })
+    }
+}
+
+impl BinCraft {
+    /// The header's `messages` total divided by the aircraft in the frame,
+    /// or `None` for an empty frame.
+    ///
+    /// The total counts every message since the server started, from every
+    /// aircraft it has heard, so this grows with uptime and only compares
+    /// frames from the same server and area. For how well individual
+    /// aircraft are heard, [`message_count_stats`](Self::message_count_stats)
+    /// and [`message_rate_stats`](Self::message_rate_stats) are more
+    /// direct.
+    pub fn avg_messages_per_aircraft(&self) -> Option<f32> {
+        if self.aircraft.is_empty() {
+            return None;
+        }
+
+        Some((self.messages as f64 / self.aircraft.len() as f64) as f32)
+    }
+
+    /// Summary of the per-aircraft message counts, or `None` if no aircraft
+    /// carries one, as in globe tiles, which carry rates instead.
+    pub fn message_count_stats(&self) -> Option<MessageStats> {
+        MessageStats::of(self.aircraft.iter().filter_map(|a| a.messages).map(f32::from))
+    }
+
+    /// Summary of the per-aircraft message rates in messages per second, or
+    /// `None` if no aircraft carries one. Only globe tiles do; see
+    /// [`Aircraft::message_rate`](crate::Aircraft::message_rate).
+    pub fn message_rate_stats(&self) -> Option<MessageStats> {
+        MessageStats::of(self.aircraft.iter().filter_map(|a| a.message_rate))
+    }
+}
@@ -0,0 +1,109 @@
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read};
+use std::path::Path;
+
+use crate::bincraft::{parse_adsb, BinCraft};
+#[cfg(any(feature = "gzip", feature = "brotli"))]
+use crate::decompress::read_limited;
+use crate::decompress::{decompress_reader, DEFAULT_MAX_DECOMPRESSED_SIZE, ZSTD_MAGIC};
+use crate::error::Error;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Compression {
+    None,
+    Zstd,
+    Gzip,
+    Brotli,
+}
+
+impl Compression {
+    /// Identifies the compression from the leading bytes. Brotli has no magic
+    /// number, so it is never detected here.
+    fn sniff(magic: &[u8]) -> Self {
+        if magic.starts_with(&ZSTD_MAGIC) {
+            Compression::Zstd
+        } else if magic.starts_with(&GZIP_MAGIC) {
+            Compression::Gzip
+        } else {
+            Compression::None
+        }
+    }
+}
+
+/// Reads uncompressed input, stopping one byte past `max_size` as
+/// `read_limited` does for decoders.
+fn read_all<R: Read>(reader: R, max_size: usize) -> Result<Vec<u8>, Error> {
+    let mut data = Vec::new();
+    reader.take(max_size as u64 + 1).read_to_end(&mut data)?;
+
+    if data.len() > max_size {
+        return Err(Error::TooLarge { limit: max_size });
+    }
+
+    Ok(data)
+}
+
+fn inflate<R: Read>(reader: R, compression: Compression, max_size: usize) -> Result<Vec<u8>, Error> {
+    match compression {
+        Compression::None => read_all(reader, max_size),
+        Compression::Zstd => decompress_reader(reader, max_size),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => read_limited(reader, |source| Ok(flate2::read::GzDecoder::new(source)), max_size),
+        #[cfg(feature = "brotli")]
+        Compression::Brotli => {
+            read_limited(reader, |source| Ok(brotli_decompressor::Decompressor::new(source, 4096)), max_size)
+        }
+        #[allow(unreachable_patterns)]
+        unsupported => {
+            Err(Error::Decompress(format!("{:?} support is not enabled", unsupported)))
+        }
+    }
+}
+
+/// Parses a binCraft body from a reader, decompressing it first if it starts
+/// with the zstd or gzip magic number. Anything else is parsed as raw
+/// binCraft. gzip needs the `gzip` feature. Input is read, and compressed
+/// input inflated, up to
+/// [`DEFAULT_MAX_DECOMPRESSED_SIZE`](crate::DEFAULT_MAX_DECOMPRESSED_SIZE).
+pub fn parse_reader<R: Read>(reader: R) -> Result<BinCraft, Error> {
+    parse_reader_with_limit(reader, DEFAULT_MAX_DECOMPRESSED_SIZE)
+}
+
+/// Like [`parse_reader`], failing with [`Error::TooLarge`] as soon as the
+/// body, after any decompression, would exceed `max_size` bytes.
+pub fn parse_reader_with_limit<R: Read>(reader: R, max_size: usize) -> Result<BinCraft, Error> {
+    parse_adsb(&read_decompressed(reader, max_size)?)
+}
+
+/// Reads everything from `reader` up to `max_size` bytes, decompressing it
+/// if it starts with the zstd or gzip magic number.
+pub(crate) fn read_decompressed<R: Read>(mut reader: R, max_size: usize) -> Result<Vec<u8>, Error> {
+    let mut magic = Vec::with_capacity(ZSTD_MAGIC.len());
+    reader.by_ref().take(ZSTD_MAGIC.len() as u64).read_to_end(&mut magic)?;
+
+    let compression = Compression::sniff(&magic);
+    let reader = Cursor::new(magic).chain(reader);
+
+    inflate(reader, compression, max_size)
+}
+
+/// Parses a binCraft dump from disk. Compression is detected as in
+/// [`parse_reader`]; since brotli streams carry no magic number, files with
+/// a `.br` extension are decoded as brotli (with the `brotli` feature).
+pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<BinCraft, Error> {
+    parse_file_with_limit(path, DEFAULT_MAX_DECOMPRESSED_SIZE)
+}
+
+/// Like [`parse_file`], with the limit of [`parse_reader_with_limit`].
+pub fn parse_file_with_limit<P: AsRef<Path>>(path: P, max_size: usize) -> Result<BinCraft, Error> {
+    let path = path.as_ref();
+    let reader = BufReader::new(File::open(path)?);
+
+    if path.extension().is_some_and(|ext| ext == "br") {
+        return parse_adsb(&inflate(reader, Compression::Brotli, max_size)?);
+    }
+
+    parse_reader_with_limit(reader, max_size)
+}
@@ -0,0 +1,88 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use futures_util::stream::{self, Stream, StreamExt};
+
+use crate::bincraft::BinCraft;
+use crate::error::Error;
+use crate::read::parse_file;
+
+/// Longest delay between two replayed frames, however far apart they are
+/// or however slow the replay.
+const MAX_DELAY: Duration = Duration::from_secs(86_400);
+
+struct Replay {
+    files: std::vec::IntoIter<PathBuf>,
+    speed: f64,
+    last_now: Option<f64>,
+}
+
+fn list_files(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut files = Vec::new();
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+
+        if entry.file_type()?.is_file() {
+            files.push(entry.path());
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+/// Replays a directory of binCraft dumps as if they were arriving live.
+///
+/// Files are read with [`parse_file`] in file-name order, so names must sort
+/// chronologically, as timestamped names do. Each frame is delayed by the
+/// gap between its `now` and the previous frame's, divided by `speed`
+/// (`2.0` plays twice as fast), and capped at a day. A `speed` that is not
+/// positive and finite replays without delays.
+///
+/// Unreadable files are yielded as errors and the replay continues, but a
+/// directory that can't be listed ends the stream after its error.
+pub fn replay<P: AsRef<Path>>(dir: P, speed: f64) -> impl Stream<Item = Result<BinCraft, Error>> {
+    let listing = list_files(dir.as_ref());
+
+    let (state, error) = match listing {
+        Ok(files) => {
+            let state = Replay {
+                files: files.into_iter(),
+                speed,
+                last_now: None,
+            };
+
+            (Some(state), None)
+        }
+        Err(e) => (None, Some(Err(e))),
+    };
+
+    let frames = stream::unfold(state, |state| async move {
+        let mut state = state?;
+        let path = state.files.next()?;
+
+        let frame = match parse_file(&path) {
+            Ok(frame) => frame,
+            Err(e) => return Some((Err(e), Some(state))),
+        };
+
+        if let Some(last_now) = state.last_now {
+            let gap = (frame.now - last_now) / state.speed;
+
+            if state.speed.is_finite() && state.speed > 0.0 && gap > 0.0 {
+                // A tiny speed can stretch the gap past what a Duration holds.
+                let delay = Duration::try_from_secs_f64(gap).map_or(MAX_DELAY, |delay| delay.min(MAX_DELAY));
+
+                tokio::time::sleep(delay).await;
+            }
+        }
+
+        state.last_now = Some(frame.now);
+
+        Some((Ok(frame), Some(state)))
+    });
+
+    stream::iter(error).chain(frames)
+}
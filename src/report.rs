@@ -0,0 +1,45 @@
+use crate::aircraft::Aircraft;
+use crate::bincraft::BinCraft;
+
+/// Just enough of a positioned aircraft to draw it on a map, for relaying
+/// to clients where bandwidth matters; see [`BinCraft::positions`].
+///
+/// With the `serde` feature it implements `Serialize`, leaving out the
+/// fields that are `None`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PositionReport {
+    pub hex: String,
+    pub lat: f32,
+    pub lon: f32,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub alt_baro: Option<i32>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub track: Option<f32>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub gs: Option<f32>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub seen_pos: Option<f32>,
+}
+
+impl PositionReport {
+    /// The report for `aircraft`, or `None` without a position.
+    pub fn from_aircraft(aircraft: &Aircraft) -> Option<Self> {
+        Some(PositionReport {
+            hex: aircraft.hex.clone(),
+            lat: aircraft.lat?,
+            lon: aircraft.lon?,
+            alt_baro: aircraft.alt_baro,
+            track: aircraft.track,
+            gs: aircraft.gs,
+            seen_pos: aircraft.seen_pos,
+        })
+    }
+}
+
+impl BinCraft {
+    /// A [`PositionReport`] for each positioned aircraft, in frame order.
+    pub fn positions(&self) -> Vec<PositionReport> {
+        self.aircraft.iter().filter_map(PositionReport::from_aircraft).collect()
+    }
+}
@@ -0,0 +1,42 @@
+//! Fixed-point scales of the binCraft record fields.
+//!
+//! readsb stores most values as integers. A `*_SCALE` is the number of raw
+//! units per unit of the decoded value, so decoding divides by it; a
+//! `*_STEP` is the decoded value of one raw unit, so decoding multiplies by
+//! it. The inverse recovers the raw value, as [`BinCraft::to_bytes`] does.
+//!
+//! [`BinCraft::to_bytes`]: crate::BinCraft::to_bytes
+
+/// `lat`, `lon`, and the receiver position in the header: microdegrees.
+pub const POSITION_SCALE: f64 = 1e6;
+
+/// `seen` and `seen_pos`: tenths of a second.
+pub const SEEN_SCALE: f32 = 10.0;
+
+/// `baro_rate` and `geom_rate`: 8 ft/min per unit.
+pub const VERTICAL_RATE_STEP: i32 = 8;
+
+/// `alt_baro` and `alt_geom`: 25 ft per unit.
+pub const ALT_STEP: i32 = 25;
+
+/// `nav_altitude_mcp` and `nav_altitude_fms`: 4 ft per unit.
+pub const NAV_ALT_STEP: u32 = 4;
+
+/// `nav_qnh`: tenths of a hectopascal.
+pub const QNH_SCALE: f32 = 10.0;
+
+/// `track`, `mag_heading`, `true_heading` and `nav_heading`: 1/90 of a
+/// degree.
+pub const HEADING_SCALE: f32 = 90.0;
+
+/// `gs`: tenths of a knot.
+pub const GS_SCALE: f32 = 10.0;
+
+/// `mach`: thousandths.
+pub const MACH_SCALE: f32 = 1e3;
+
+/// `roll` in degrees and `track_rate` in degrees per second: hundredths.
+pub const ROLL_SCALE: f32 = 100.0;
+
+/// `message_rate` in globe tiles: tenths of a message per second.
+pub const MESSAGE_RATE_SCALE: f32 = 10.0;
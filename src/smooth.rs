@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use crate::bincraft::BinCraft;
+use crate::icao::Icao;
+
+/// A track not updated for this many seconds starts over at its next fix
+/// rather than being pulled towards a stale estimate.
+const MAX_GAP_SECS: f64 = 60.0;
+
+/// Nautical miles per degree of latitude.
+const NM_PER_DEG: f64 = 60.0;
+
+/// The filter state of one aircraft.
+#[derive(Clone, Copy, Debug)]
+struct Track {
+    /// Frame time of the last fix folded in.
+    time: f64,
+    lat: f64,
+    lon: f64,
+    /// Degrees per second.
+    lat_rate: f64,
+    lon_rate: f64,
+    gs: Option<f32>,
+    track: Option<f32>,
+}
+
+/// The difference `to - from` between two longitudes, or two bearings, taken
+/// the short way round: `-180..180`.
+fn wrap(to: f64, from: f64) -> f64 {
+    (to - from + 540.0).rem_euclid(360.0) - 180.0
+}
+
+/// An alpha-beta filter over the positions of successive frames, keyed by
+/// ICAO address, for steadier tracks on a map than the raw positions give,
+/// MLAT ones especially.
+///
+/// Each new fix pulls the predicted position towards the measured one by
+/// `alpha` and corrects the estimated velocity by `beta`. Lower values
+/// smooth more but lag more on turns. Ground speed and track are smoothed
+/// by `alpha` too.
+///
+/// Feed it every frame in order, such as from `stream_box`, after any
+/// merging with [`BinCraft::merge`] or [`BinCraft::merge_newest`]; see
+/// [`PositionSmoother::update`].
+#[derive(Clone, Debug)]
+pub struct PositionSmoother {
+    alpha: f64,
+    beta: f64,
+    tracks: HashMap<Icao, Track>,
+}
+
+impl Default for PositionSmoother {
+    /// `alpha` 0.5 and `beta` 0.15, close to critically damped, which
+    /// takes out most MLAT jitter while keeping up with turns.
+    fn default() -> Self {
+        PositionSmoother::new(0.5, 0.15)
+    }
+}
+
+impl PositionSmoother {
+    /// A smoother with the given gains, each clamped to `0.0..=1.0`.
+    pub fn new(alpha: f64, beta: f64) -> Self {
+        PositionSmoother {
+            alpha: alpha.clamp(0.0, 1.0),
+            beta: beta.clamp(0.0, 1.0),
+            tracks: HashMap::new(),
+        }
+    }
+
+    /// Folds the positions in `frame` into the filter and replaces each
+    /// positioned aircraft's `lat`, `lon`, `gs` and `track` with the
+    /// smoothed values. An aircraft's first fix, and its first after more
+    /// than a minute without one, is passed through unchanged. Aircraft
+    /// without a position, or with a hand-built `hex` that is not an
+    /// address, are left alone.
+    ///
+    /// Tracks not updated for a minute are forgotten.
+    pub fn update(&mut self, frame: &mut BinCraft) {
+        let now = frame.now;
+
+        for aircraft in &mut frame.aircraft {
+            let (Some(lat), Some(lon), Some(icao)) = (aircraft.lat, aircraft.lon, aircraft.icao()) else {
+                continue;
+            };
+
+            // When the fix was taken, so repeated fixes are not counted
+            // twice and velocity is measured over the real interval.
+            let time = now - aircraft.seen_pos.unwrap_or(0.0) as f64;
+
+            let fix = Track {
+                time,
+                lat: lat as f64,
+                lon: lon as f64,
+                lat_rate: 0.0,
+                lon_rate: 0.0,
+                gs: aircraft.gs,
+                track: aircraft.track,
+            };
+
+            let track = match self.tracks.get(&icao) {
+                Some(track) if time - track.time > MAX_GAP_SECS => self.start(fix),
+                Some(track) => self.step(track, fix),
+                None => self.start(fix),
+            };
+
+            self.tracks.insert(icao, track);
+
+            aircraft.lat = Some(track.lat as f32);
+            aircraft.lon = Some(track.lon as f32);
+            aircraft.gs = track.gs;
+            aircraft.track = track.track;
+        }
+
+        self.tracks.retain(|_, track| now - track.time <= MAX_GAP_SECS);
+    }
+
+    /// The smoothed position of `icao` extrapolated to frame time `time`,
+    /// for animating between frames, or `None` for an aircraft not being
+    /// tracked.
+    pub fn position_at(&self, icao: Icao, time: f64) -> Option<(f64, f64)> {
+        let track = self.tracks.get(&icao)?;
+        let elapsed = time - track.time;
+
+        let lat = (track.lat + track.lat_rate * elapsed).clamp(-90.0, 90.0);
+        let lon = wrap(track.lon + track.lon_rate * elapsed, 0.0);
+
+        Some((lat, lon))
+    }
+
+    /// Forgets every track.
+    pub fn reset(&mut self) {
+        self.tracks.clear();
+    }
+
+    /// A new track at `fix`, moving as its reported speed and track say.
+    fn start(&self, fix: Track) -> Track {
+        let (Some(gs), Some(track)) = (fix.gs, fix.track) else {
+            return fix;
+        };
+
+        let (east, north) = (track as f64).to_radians().sin_cos();
+        let deg_per_sec = gs as f64 / 3600.0 / NM_PER_DEG;
+        let cos_lat = fix.lat.to_radians().cos().max(1e-6);
+
+        Track {
+            lat_rate: deg_per_sec * north,
+            lon_rate: deg_per_sec * east / cos_lat,
+            ..fix
+        }
+    }
+
+    /// `track` advanced to `fix` and corrected towards it.
+    fn step(&self, track: &Track, fix: Track) -> Track {
+        let elapsed = fix.time - track.time;
+
+        // The same fix again, or one older than what is already in.
+        if elapsed <= 0.0 {
+            return *track;
+        }
+
+        let lat = track.lat + track.lat_rate * elapsed;
+        let lon = track.lon + track.lon_rate * elapsed;
+
+        let lat_residual = fix.lat - lat;
+        let lon_residual = wrap(fix.lon, lon);
+
+        let blend = |smoothed: Option<f32>, reported: Option<f32>, difference: fn(f64, f64) -> f64| {
+            match (smoothed, reported) {
+                (Some(smoothed), Some(reported)) => {
+                    let (smoothed, reported) = (smoothed as f64, reported as f64);
+                    Some((smoothed + self.alpha * difference(reported, smoothed)) as f32)
+                }
+                (_, reported) => reported,
+            }
+        };
+
+        Track {
+            time: fix.time,
+            lat: (lat + self.alpha * lat_residual).clamp(-90.0, 90.0),
+            lon: wrap(lon + self.alpha * lon_residual, 0.0),
+            lat_rate: track.lat_rate + self.beta * lat_residual / elapsed,
+            lon_rate: track.lon_rate + self.beta * lon_residual / elapsed,
+            gs: blend(track.gs, fix.gs, |to, from| to - from),
+            track: blend(track.track, fix.track, wrap).map(|track| track.rem_euclid(360.0)),
+        }
+    }
+}
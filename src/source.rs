@@ -0,0 +1,90 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::bincraft::BinCraft;
+use crate::error::Error;
+#[cfg(feature = "net")]
+use crate::fetch::{fetch_box_split, Client};
+#[cfg(feature = "net")]
+use crate::geo::BoundingBox;
+use crate::read::{parse_file, parse_reader};
+
+/// Anything that produces the current frame on demand: the live API, a dump
+/// on disk, or canned frames in a test.
+///
+/// `stream_source` polls any source, so code built on it runs the same
+/// against a self-hosted readsb or without network access at all.
+pub trait AircraftSource {
+    /// Fetches or reads the current frame.
+    fn snapshot(&self) -> impl Future<Output = Result<BinCraft, Error>> + Send;
+}
+
+/// The aircraft inside a box, fetched from the API as in
+/// [`fetch_box_split`].
+#[cfg(feature = "net")]
+#[derive(Clone, Debug)]
+pub struct BoxSource {
+    pub client: Client,
+    pub bbox: BoundingBox,
+}
+
+#[cfg(feature = "net")]
+impl BoxSource {
+    pub fn new(client: Client, bbox: BoundingBox) -> Self {
+        BoxSource { client, bbox }
+    }
+}
+
+#[cfg(feature = "net")]
+impl AircraftSource for BoxSource {
+    fn snapshot(&self) -> impl Future<Output = Result<BinCraft, Error>> + Send {
+        fetch_box_split(&self.client, &self.bbox)
+    }
+}
+
+/// A dump on disk, re-read on every snapshot so that a file a local readsb
+/// keeps rewriting is picked up fresh. Read as in [`parse_file`], which
+/// blocks; dumps are small enough for that not to matter.
+#[derive(Clone, Debug)]
+pub struct FileSource {
+    pub path: PathBuf,
+}
+
+impl FileSource {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        FileSource { path: path.into() }
+    }
+}
+
+impl AircraftSource for FileSource {
+    async fn snapshot(&self) -> Result<BinCraft, Error> {
+        parse_file(&self.path)
+    }
+}
+
+/// Canned binCraft bodies, handed out in order, for tests. Once the last
+/// body is reached it is repeated. Bodies may be compressed as accepted by
+/// [`parse_reader`]; [`BinCraft::to_bytes`] turns a built frame into one.
+/// A fixture without bodies fails every snapshot, as an empty body does,
+/// with [`Error::Truncated`].
+#[derive(Debug)]
+pub struct Fixture {
+    bodies: Vec<Vec<u8>>,
+    next: AtomicUsize,
+}
+
+impl Fixture {
+    pub fn new(bodies: Vec<Vec<u8>>) -> Self {
+        Fixture { bodies, next: AtomicUsize::new(0) }
+    }
+}
+
+impl AircraftSource for Fixture {
+    async fn snapshot(&self) -> Result<BinCraft, Error> {
+        let index = self.next.fetch_add(1, Ordering::Relaxed).min(self.bodies.len().saturating_sub(1));
+        let body = self.bodies.get(index).map_or(&[][..], Vec::as_slice);
+
+        parse_reader(body)
+    }
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Error;
+
+/// A Mode A transponder code, stored as in binCraft with one digit per
+/// nibble, so `7700` is `0x7700` and `0021` is `0x0021`.
+///
+/// The transponder sends the code as four groups of three bits (A1A2A4,
+/// B1B2B4, C1C2C4, D1D2D4) interleaved in the reply; readsb sorts those
+/// into octal digits before storing them, so formatting the nibbles as hex
+/// gives the code as written. A nibble of 8 or more is therefore not a
+/// digit at all; see [`Squawk::is_valid`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Squawk(u16);
+
+impl Squawk {
+    /// Wraps a nibble-per-digit code as found in the binCraft record.
+    pub const fn from_raw(raw: u16) -> Self {
+        Squawk(raw)
+    }
+
+    /// Builds a code from its four digits, most significant first, or `None`
+    /// if any digit is outside `0..=7`.
+    pub fn from_digits(digits: [u8; 4]) -> Option<Self> {
+        if digits.iter().any(|d| *d > 7) {
+            return None;
+        }
+
+        Some(Squawk(digits.iter().fold(0, |raw, d| (raw << 4) | *d as u16)))
+    }
+
+    pub const fn raw(&self) -> u16 {
+        self.0
+    }
+
+    /// The four digits, most significant first.
+    pub fn digits(&self) -> [u8; 4] {
+        [12, 8, 4, 0].map(|shift| ((self.0 >> shift) & 15) as u8)
+    }
+
+    /// Whether every digit is octal, as a real Mode A code must be.
+    pub fn is_valid(&self) -> bool {
+        self.digits().iter().all(|d| *d <= 7)
+    }
+
+    /// A short label for well-known special-purpose codes, or `None` for
+    /// ordinary discrete codes. Some labels are regional: `1200` is VFR in
+    /// North America, `7000` in most of Europe.
+    pub fn meaning(&self) -> Option<&'static str> {
+        match self.0 {
+            0x7500 => Some("hijack"),
+            0x7600 => Some("radio failure"),
+            0x7700 => Some("emergency"),
+            0x7400 => Some("UAV lost link"),
+            0x7777 => Some("military interception"),
+            0x1200 => Some("VFR"),
+            0x7000 => Some("VFR (Europe)"),
+            0x2000 => Some("non-discrete, entering SSR airspace"),
+            0x1000 => Some("Mode S conspicuity"),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Squawk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04x}", self.0)
+    }
+}
+
+impl FromStr for Squawk {
+    type Err = Error;
+
+    /// Parses four octal digits, keeping leading zeros significant.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidSquawk(s.to_string());
+
+        let digits = s.as_bytes();
+
+        if digits.len() != 4 {
+            return Err(invalid());
+        }
+
+        let mut code = [0u8; 4];
+
+        for (digit, c) in code.iter_mut().zip(digits) {
+            *digit = (*c as char).to_digit(8).ok_or_else(invalid)? as u8;
+        }
+
+        Squawk::from_digits(code).ok_or_else(invalid)
+    }
+}
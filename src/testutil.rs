@@ -0,0 +1,183 @@
+//! Reproducible frames for testing code built on this crate without the
+//! network, behind the `test-util` feature.
+//!
+//! [`FrameBuilder`] assembles a frame from hand-built aircraft and encodes
+//! it with [`BinCraft::to_bytes`], so tests see exactly what the decoder
+//! would produce from a server. [`synthetic_aircraft`] fills a frame with
+//! varied aircraft from a seed, and [`FrameBuilder::advance`] moves them on
+//! for the next frame of a stream.
+//!
+//! ```
+//! use adsbexchange::testutil::{synthetic_aircraft, FrameBuilder};
+//! use adsbexchange::{BoundingBox, Fixture};
+//!
+//! let bbox = BoundingBox::new(50.0, 52.0, -1.0, 1.0);
+//! let frame = || FrameBuilder::new(1_000.0).with_aircraft(synthetic_aircraft(7, 20, &bbox));
+//!
+//! // Two polls of a server five seconds apart, for stream_source.
+//! let source = Fixture::new(vec![frame().to_bytes(), frame().advance(5.0).to_bytes()]);
+//! # drop(source);
+//! assert_eq!(frame().build().aircraft.len(), 20);
+//! ```
+
+use crate::aircraft::{AirGround, Aircraft, SignalType};
+use crate::bincraft::{parse_adsb, BinCraft};
+use crate::geo::{self, BoundingBox};
+use crate::squawk::Squawk;
+
+/// Record and header size of the frames built here.
+const STRIDE: usize = 112;
+
+/// A frame under construction. Everything not set is zero, as from a
+/// server that reports none of it.
+#[derive(Debug, Default)]
+pub struct FrameBuilder {
+    now: f64,
+    messages: u32,
+    globe_index: u32,
+    /// The area the header reports covering, as south, north, west, east.
+    served: (i16, i16, i16, i16),
+    receiver: (f64, f64),
+    aircraft: Vec<Aircraft>,
+}
+
+impl FrameBuilder {
+    /// An empty frame at `now`, in seconds since the Unix epoch.
+    pub fn new(now: f64) -> Self {
+        FrameBuilder { now, ..FrameBuilder::default() }
+    }
+
+    pub fn with_aircraft(mut self, aircraft: impl IntoIterator<Item = Aircraft>) -> Self {
+        self.aircraft.extend(aircraft);
+        self
+    }
+
+    /// The header's cumulative `messages` counter.
+    pub fn with_messages(mut self, messages: u32) -> Self {
+        self.messages = messages;
+        self
+    }
+
+    /// Marks the frame as a globe tile, which carries per-aircraft message
+    /// rates instead of counts.
+    pub fn with_globe_index(mut self, globe_index: u32) -> Self {
+        self.globe_index = globe_index;
+        self
+    }
+
+    /// The area the header reports covering, in whole degrees, as a server
+    /// does for box requests.
+    pub fn with_served_box(mut self, south: i16, north: i16, west: i16, east: i16) -> Self {
+        self.served = (south, north, west, east);
+        self
+    }
+
+    pub fn with_receiver(mut self, lat: f64, lon: f64) -> Self {
+        self.receiver = (lat, lon);
+        self
+    }
+
+    /// The same frame `seconds` later: `now` advances, and each aircraft
+    /// with a position, ground speed and track moves along its track. Ages
+    /// such as `seen` are left as they are.
+    pub fn advance(mut self, seconds: f64) -> Self {
+        self.now += seconds;
+
+        for aircraft in &mut self.aircraft {
+            let (Some(lat), Some(lon), Some(gs), Some(track)) = (aircraft.lat, aircraft.lon, aircraft.gs, aircraft.track)
+            else {
+                continue;
+            };
+
+            let (lat, lon) = geo::destination(lat as f64, lon as f64, track as f64, gs as f64 / 3600.0 * seconds);
+
+            aircraft.lat = Some(lat as f32);
+            aircraft.lon = Some(lon as f32);
+        }
+
+        self
+    }
+
+    /// The frame as an uncompressed binCraft body, as a server sends it.
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.build_unencoded().to_bytes()
+    }
+
+    /// The frame as decoded from [`FrameBuilder::to_bytes`], with every
+    /// value rounded to what the format can carry.
+    pub fn build(self) -> BinCraft {
+        parse_adsb(&self.to_bytes()).expect("an encoded frame always parses")
+    }
+
+    /// The frame with the values as given, without the round trip through
+    /// the format that [`FrameBuilder::build`] makes, for tests of fields
+    /// binCraft does not carry or of exact values.
+    pub fn build_unencoded(self) -> BinCraft {
+        let mut header = vec![0u8; STRIDE];
+        header[8..12].copy_from_slice(&(STRIDE as u32).to_le_bytes());
+
+        let mut frame = parse_adsb(&header).expect("an empty frame always parses");
+
+        frame.now = self.now;
+        frame.messages = self.messages;
+        frame.globe_index = self.globe_index;
+        (frame.south, frame.north, frame.west, frame.east) = self.served;
+        (frame.receiver_lat, frame.receiver_lon) = self.receiver;
+        frame.aircraft = self.aircraft;
+
+        frame
+    }
+}
+
+/// A small xorshift generator, so the same seed gives the same aircraft on
+/// every platform and release.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // Zero would stay zero forever.
+        Rng(seed ^ 0x9e37_79b9_7f4a_7c15)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform in `low..high`.
+    fn range(&mut self, low: f64, high: f64) -> f64 {
+        low + (self.next() >> 11) as f64 / (1u64 << 53) as f64 * (high - low)
+    }
+}
+
+/// `count` airborne ADS-B aircraft spread over `bbox`, the same for the
+/// same `seed`. Hexes count up from `400000`, callsigns from `TST0000`;
+/// altitude, ground speed, track and squawk vary.
+pub fn synthetic_aircraft(seed: u64, count: usize, bbox: &BoundingBox) -> Vec<Aircraft> {
+    let mut rng = Rng::new(seed);
+
+    // Longitudes are drawn over the width of the box, wrapping past 180°
+    // for one that crosses the antimeridian.
+    let width = if bbox.crosses_antimeridian() { bbox.east - bbox.west + 360.0 } else { bbox.east - bbox.west };
+
+    (0..count)
+        .map(|i| {
+            let lat = rng.range(bbox.south, bbox.north);
+            let lon = (bbox.west + rng.range(0.0, width) + 540.0).rem_euclid(360.0) - 180.0;
+
+            let digits = [0, 0, 0, 0].map(|_: u8| (rng.next() % 8) as u8);
+
+            Aircraft::new(format!("{:06x}", 0x400000 + i))
+                .with_flight(format!("TST{:04}", i % 10_000))
+                .with_position(lat as f32, lon as f32)
+                .with_alt_baro(rng.range(1_000.0, 41_000.0) as i32 / 25 * 25)
+                .with_velocity(rng.range(120.0, 500.0) as f32, rng.range(0.0, 360.0) as f32)
+                .with_squawk(Squawk::from_digits(digits).expect("digits are octal"))
+                .with_signal_type(SignalType::AdsbIcao)
+                .with_airground(AirGround::Airborne)
+                .with_seen(rng.range(0.0, 10.0) as f32)
+        })
+        .collect()
+}
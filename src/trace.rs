@@ -0,0 +1,141 @@
+use serde_json::Value;
+
+use crate::decompress::DEFAULT_MAX_DECOMPRESSED_SIZE;
+use crate::error::Error;
+use crate::read::read_decompressed;
+
+/// One aircraft's position history, as served by readsb in its
+/// `trace_recent_<hex>.json` and `trace_full_<hex>.json` files.
+#[derive(Clone, Debug, Default)]
+pub struct Trace {
+    pub hex: String,
+    pub registration: Option<String>,
+    /// ICAO type designator, e.g. `A320`.
+    pub type_code: Option<String>,
+    pub db_flags: u16,
+    /// Unix time the point offsets are relative to.
+    pub timestamp: f64,
+    pub points: Vec<TracePoint>,
+}
+
+/// A single reported position in a [`Trace`].
+#[derive(Clone, Debug, Default)]
+pub struct TracePoint {
+    /// Unix time of the position.
+    pub time: f64,
+    pub lat: f64,
+    pub lon: f64,
+    /// Barometric altitude in feet; `None` on the ground or when unknown.
+    pub alt_baro: Option<i32>,
+    pub alt_geom: Option<i32>,
+    pub on_ground: bool,
+    pub gs: Option<f32>,
+    pub track: Option<f32>,
+    pub baro_rate: Option<i32>,
+    pub geom_rate: Option<i32>,
+    pub ias: Option<u16>,
+    pub roll: Option<f32>,
+    /// Position source as readsb names it, e.g. `adsb_icao` or `mlat`.
+    pub source: Option<String>,
+    /// readsb's point flags; see [`is_stale`](Self::is_stale) and
+    /// [`starts_leg`](Self::starts_leg).
+    pub flags: u8,
+}
+
+impl TracePoint {
+    /// The position was not updated since the previous point.
+    pub fn is_stale(&self) -> bool {
+        self.flags & 1 != 0
+    }
+
+    /// readsb considers this point the start of a new flight leg.
+    pub fn starts_leg(&self) -> bool {
+        self.flags & 2 != 0
+    }
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidTrace(reason.to_string())
+}
+
+fn string(value: Option<&Value>) -> Option<String> {
+    value.and_then(Value::as_str).map(str::to_string)
+}
+
+fn parse_point(point: &Value, timestamp: f64) -> Result<TracePoint, Error> {
+    let point = point.as_array().ok_or_else(|| invalid("trace point is not an array"))?;
+
+    let f64_at = |i: usize| point.get(i).and_then(Value::as_f64);
+    let i32_at = |i: usize| f64_at(i).map(|v| v as i32);
+
+    let (Some(offset), Some(lat), Some(lon)) = (f64_at(0), f64_at(1), f64_at(2)) else {
+        return Err(invalid("trace point is missing its time or position"));
+    };
+
+    let flags = f64_at(6).unwrap_or(0.0) as u8;
+
+    let mut tp = TracePoint {
+        time: timestamp + offset,
+        lat,
+        lon,
+        on_ground: point.get(3).and_then(Value::as_str) == Some("ground"),
+        gs: f64_at(4).map(|v| v as f32),
+        track: f64_at(5).map(|v| v as f32),
+        alt_geom: i32_at(10),
+        geom_rate: i32_at(11),
+        ias: f64_at(12).map(|v| v as u16),
+        roll: f64_at(13).map(|v| v as f32),
+        source: string(point.get(9)),
+        flags,
+        ..TracePoint::default()
+    };
+
+    // The altitude and rate slots are geometric rather than barometric when
+    // the corresponding flag is set.
+    if flags & 8 != 0 {
+        tp.alt_geom = i32_at(3);
+    } else {
+        tp.alt_baro = i32_at(3);
+    }
+
+    if flags & 4 != 0 {
+        tp.geom_rate = i32_at(7);
+    } else {
+        tp.baro_rate = i32_at(7);
+    }
+
+    Ok(tp)
+}
+
+/// Parses a readsb trace file. Trace files are normally stored gzipped;
+/// gzip-compressed input is decoded with the `gzip` feature, up to
+/// [`DEFAULT_MAX_DECOMPRESSED_SIZE`](crate::DEFAULT_MAX_DECOMPRESSED_SIZE).
+pub fn parse_trace(data: &[u8]) -> Result<Trace, Error> {
+    parse_trace_with_limit(data, DEFAULT_MAX_DECOMPRESSED_SIZE)
+}
+
+/// Like [`parse_trace`], failing with [`Error::TooLarge`] as soon as the
+/// file, after any decompression, would exceed `max_size` bytes.
+pub fn parse_trace_with_limit(data: &[u8], max_size: usize) -> Result<Trace, Error> {
+    let data = read_decompressed(data, max_size)?;
+    let value: Value = serde_json::from_slice(&data).map_err(Error::Json)?;
+
+    let hex = string(value.get("icao")).ok_or_else(|| invalid("missing icao"))?;
+    let timestamp = value.get("timestamp").and_then(Value::as_f64).ok_or_else(|| invalid("missing timestamp"))?;
+
+    let points = value.get("trace")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("missing trace"))?
+        .iter()
+        .map(|point| parse_point(point, timestamp))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Trace {
+        hex,
+        registration: string(value.get("r")),
+        type_code: string(value.get("t")),
+        db_flags: value.get("dbFlags").and_then(Value::as_u64).unwrap_or(0) as u16,
+        timestamp,
+        points,
+    })
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+
+use crate::bincraft::BinCraft;
+use crate::geo::BoundingBox;
+
+/// Something unexpected about a fetched frame that is not worth failing
+/// over, collected in [`BinCraft::warnings`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Warning {
+    /// The server covered a different area than requested, usually a
+    /// smaller one: readsb clamps boxes larger than it is configured to
+    /// serve. Aircraft outside `served` are missing from the frame.
+    BoxClamped { requested: BoundingBox, served: BoundingBox },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::BoxClamped { requested, served } => write!(
+                f,
+                "requested box {},{},{},{} but the server covered {},{},{},{}",
+                requested.south, requested.north, requested.west, requested.east,
+                served.south, served.north, served.west, served.east,
+            ),
+        }
+    }
+}
+
+impl BinCraft {
+    /// The area the header says the frame covers, from `south`, `west`,
+    /// `north` and `east`, or `None` when the server left them all zero, as
+    /// for requests that are not boxes. The header holds whole degrees.
+    pub fn served_box(&self) -> Option<BoundingBox> {
+        if (self.south, self.west, self.north, self.east) == (0, 0, 0, 0) {
+            return None;
+        }
+
+        Some(BoundingBox::new(self.south as f64, self.north as f64, self.west as f64, self.east as f64))
+    }
+
+    /// A [`Warning::BoxClamped`] if any edge of [`BinCraft::served_box`]
+    /// lies more than `tolerance_deg` degrees from the same edge of
+    /// `requested`. Frames whose header reports no area never warn.
+    pub fn box_clamp(&self, requested: &BoundingBox, tolerance_deg: f64) -> Option<Warning> {
+        let served = self.served_box()?;
+
+        let edges = [
+            (requested.south, served.south),
+            (requested.north, served.north),
+            (requested.west, served.west),
+            (requested.east, served.east),
+        ];
+
+        edges
+            .iter()
+            .any(|(requested, served)| (requested - served).abs() > tolerance_deg)
+            .then_some(Warning::BoxClamped { requested: *requested, served })
+    }
+}
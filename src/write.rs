@@ -0,0 +1,289 @@
+#[cfg(feature = "gzip")]
+use std::io::Write;
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::aircraft::{AirGround, Aircraft, SignalType};
+use crate::bincraft::{BinCraft, RawHeader, HEADER_LEN};
+#[cfg(feature = "gzip")]
+use crate::error::Error;
+use crate::icao::Icao;
+use crate::scale::{
+    ALT_STEP, GS_SCALE, HEADING_SCALE, MACH_SCALE, MESSAGE_RATE_SCALE, NAV_ALT_STEP, POSITION_SCALE, QNH_SCALE,
+    ROLL_SCALE, SEEN_SCALE, VERTICAL_RATE_STEP,
+};
+
+/// Stride of written records, the one that carries every field.
+const STRIDE: usize = 112;
+
+/// Version written to the header; the record layout matches it.
+const BIN_CRAFT_VERSION: u32 = 20240218;
+
+/// Nav mode names in the order of their bits.
+const NAV_MODES: [&str; 6] = ["autopilot", "vnav", "alt_hold", "approach", "lnav", "tcas"];
+
+fn signal_type_code(signal_type: &SignalType) -> u8 {
+    match signal_type {
+        SignalType::AdsbIcao => 0,
+        SignalType::AdsbIcaoNt => 1,
+        SignalType::AdsrIcao => 2,
+        SignalType::TisbIcao => 3,
+        SignalType::Adsc => 4,
+        SignalType::Mlat => 5,
+        SignalType::Other => 6,
+        SignalType::ModeS => 7,
+        SignalType::AdsbOther => 8,
+        SignalType::AdsrOther => 9,
+        SignalType::TisbTrackfile => 10,
+        SignalType::TisbOther => 11,
+        SignalType::ModeAc => 12,
+        SignalType::Unknown => 13,
+    }
+}
+
+fn airground_code(airground: AirGround) -> u8 {
+    match airground {
+        AirGround::Invalid => 0,
+        AirGround::Ground => 1,
+        AirGround::Airborne => 2,
+        AirGround::Uncertain => 3,
+    }
+}
+
+/// The byte readsb derived `rssi` from, inverting the decoder's dBFS
+/// formula.
+fn rssi_byte(rssi: f64) -> u8 {
+    ((10f64.powf(rssi / 10.0) - 1125e-8).max(0.0) * 65025.0).sqrt().round().min(255.0) as u8
+}
+
+/// Copies `text` into `field`; the rest of the field keeps `pad`.
+fn put_str(field: &mut [u8], text: &str, pad: u8) {
+    field.fill(pad);
+
+    for (dst, src) in field.iter_mut().zip(text.bytes()) {
+        *dst = src;
+    }
+}
+
+/// `value` in whole multiples of `step`, rounded to the nearest, or zero
+/// when unset.
+fn stepped<T: Into<f64>>(value: Option<T>, step: T) -> f64 {
+    value.map_or(0.0, |v| (v.into() / step.into()).round())
+}
+
+/// Encodes one aircraft as a record, the inverse of `build_aircraft`.
+fn encode_aircraft(aircraft: &Aircraft, icao: Icao, use_message_rate: bool, record: &mut [u8]) {
+    // Indices match tar1090's typed views, as in the decoder.
+    let u32 = |record: &mut [u8], i: usize, v: u32| LittleEndian::write_u32(&mut record[i * 4..i * 4 + 4], v);
+    let s32 = |record: &mut [u8], i: usize, v: i32| LittleEndian::write_i32(&mut record[i * 4..i * 4 + 4], v);
+    let u16 = |record: &mut [u8], i: usize, v: u16| LittleEndian::write_u16(&mut record[i * 2..i * 2 + 2], v);
+    let s16 = |record: &mut [u8], i: usize, v: i16| LittleEndian::write_i16(&mut record[i * 2..i * 2 + 2], v);
+
+    let scaled = |value: Option<f32>, scale: f32| value.map_or(0, |v| (v * scale).round() as i16);
+
+    u32(record, 0, icao.raw());
+
+    u16(record, 2, aircraft.seen_pos.map_or(0, |v| (v * SEEN_SCALE).round() as u16));
+    u16(record, 3, aircraft.seen.map_or(0, |v| (v * SEEN_SCALE).round() as u16));
+
+    match (aircraft.lat, aircraft.lon) {
+        (Some(lat), Some(lon)) => {
+            s32(record, 2, (lon as f64 * POSITION_SCALE).round() as i32);
+            s32(record, 3, (lat as f64 * POSITION_SCALE).round() as i32);
+        }
+        _ => s32(record, 3, i32::MAX),
+    }
+
+    s16(record, 8, stepped(aircraft.baro_rate, VERTICAL_RATE_STEP) as i16);
+    s16(record, 9, stepped(aircraft.geom_rate, VERTICAL_RATE_STEP) as i16);
+    s16(record, 10, stepped(aircraft.alt_baro, ALT_STEP) as i16);
+    s16(record, 11, stepped(aircraft.alt_geom, ALT_STEP) as i16);
+    u16(record, 12, stepped(aircraft.nav_altitude_mcp, NAV_ALT_STEP) as u16);
+    u16(record, 13, stepped(aircraft.nav_altitude_fms, NAV_ALT_STEP) as u16);
+    s16(record, 14, scaled(aircraft.nav_qnh, QNH_SCALE));
+    s16(record, 15, scaled(aircraft.nav_heading, HEADING_SCALE));
+    u16(record, 16, aircraft.squawk.map_or(0, |squawk| squawk.raw()));
+    s16(record, 17, scaled(aircraft.gs, GS_SCALE));
+    s16(record, 18, scaled(aircraft.mach, MACH_SCALE));
+    s16(record, 19, scaled(aircraft.roll, ROLL_SCALE));
+    s16(record, 20, scaled(aircraft.track, HEADING_SCALE));
+    s16(record, 21, scaled(aircraft.track_rate, ROLL_SCALE));
+    s16(record, 22, scaled(aircraft.mag_heading, HEADING_SCALE));
+    s16(record, 23, scaled(aircraft.true_heading, HEADING_SCALE));
+    s16(record, 24, aircraft.wd.unwrap_or(0));
+    s16(record, 25, aircraft.ws.unwrap_or(0));
+    s16(record, 26, aircraft.oat.unwrap_or(0));
+    s16(record, 27, aircraft.tat.unwrap_or(0));
+    u16(record, 28, aircraft.tas.unwrap_or(0));
+    u16(record, 29, aircraft.ias.unwrap_or(0));
+    u16(record, 30, aircraft.quality.rc);
+
+    if use_message_rate {
+        u16(record, 31, aircraft.message_rate.map_or(0, |v| (v * MESSAGE_RATE_SCALE).round() as u16));
+    } else {
+        u16(record, 31, aircraft.messages.unwrap_or(0));
+    }
+
+    record[64] = aircraft.category.map_or(0, |category| category.raw());
+
+    let quality = &aircraft.quality;
+
+    record[65] = quality.nic;
+
+    record[66] = NAV_MODES
+        .iter()
+        .enumerate()
+        .filter(|(_, mode)| aircraft.nav_modes.contains(mode))
+        .fold(0, |bits, (bit, _)| bits | 1 << bit);
+
+    record[67] = aircraft.emergency.unwrap_or(0) & 15
+        | aircraft.signal_type.as_ref().map_or(0, signal_type_code) << 4;
+    record[68] = airground_code(aircraft.airground) | aircraft.nav_altitude_src.unwrap_or(0) << 4;
+    record[69] = quality.sil_type & 15 | quality.adsb_version << 4;
+    record[70] = quality.adsr_version & 15 | quality.tisb_version << 4;
+    record[71] = quality.nac_p.unwrap_or(0) & 15 | quality.nac_v.unwrap_or(0) << 4;
+    record[72] = quality.sil.unwrap_or(0) & 3
+        | (quality.gva.unwrap_or(0) & 3) << 2
+        | (quality.sda.unwrap_or(0) & 3) << 4
+        | (quality.nic_a.unwrap_or(0) & 1) << 6
+        | (quality.nic_c.unwrap_or(0) & 1) << 7;
+
+    let bits = |flags: &[bool]| {
+        flags.iter().enumerate().fold(0u8, |bits, (bit, set)| bits | (*set as u8) << bit)
+    };
+
+    // The low three bits of byte 73 are values, not validity flags.
+    record[73] = bits(&[
+        quality.nic_baro == Some(true),
+        aircraft.alert1 == Some(true),
+        aircraft.spi == Some(true),
+        aircraft.flight.is_some(),
+        aircraft.alt_baro.is_some(),
+        aircraft.alt_geom.is_some(),
+        aircraft.lat.is_some() && aircraft.lon.is_some(),
+        aircraft.gs.is_some(),
+    ]);
+    record[74] = bits(&[
+        aircraft.ias.is_some(),
+        aircraft.tas.is_some(),
+        aircraft.mach.is_some(),
+        aircraft.track.is_some(),
+        aircraft.track_rate.is_some(),
+        aircraft.roll.is_some(),
+        aircraft.mag_heading.is_some(),
+        aircraft.true_heading.is_some(),
+    ]);
+    record[75] = bits(&[
+        aircraft.baro_rate.is_some(),
+        aircraft.geom_rate.is_some(),
+        quality.nic_a.is_some(),
+        quality.nic_c.is_some(),
+        quality.nic_baro.is_some(),
+        quality.nac_p.is_some(),
+        quality.nac_v.is_some(),
+        quality.sil.is_some(),
+    ]);
+    record[76] = bits(&[
+        quality.gva.is_some(),
+        quality.sda.is_some(),
+        aircraft.squawk.is_some(),
+        aircraft.emergency.is_some(),
+        aircraft.spi.is_some(),
+        aircraft.nav_qnh.is_some(),
+        aircraft.nav_altitude_mcp.is_some(),
+        aircraft.nav_altitude_fms.is_some(),
+    ]);
+    record[77] = bits(&[
+        aircraft.nav_altitude_src.is_some(),
+        aircraft.nav_heading.is_some(),
+        !aircraft.nav_modes.is_empty(),
+        aircraft.alert1.is_some(),
+        aircraft.wd.is_some() && aircraft.ws.is_some(),
+        aircraft.oat.is_some() && aircraft.tat.is_some(),
+    ]);
+
+    put_str(&mut record[78..86], aircraft.flight.as_deref().unwrap_or(""), b' ');
+    u16(record, 43, aircraft.db_flags);
+    put_str(&mut record[88..92], &aircraft.tail, 0);
+    put_str(&mut record[92..104], &aircraft.registration, 0);
+
+    record[104] = aircraft.receiver_count;
+    record[105] = rssi_byte(aircraft.rssi);
+    record[106] = aircraft.extra_flags;
+
+    // The receiver id is written by the decoder as "xx-xxxx-xxxx".
+    let r_id = aircraft.r_id.as_deref().and_then(|r_id| {
+        let mut parts = r_id.split('-').map(|part| u32::from_str_radix(part, 16).ok());
+
+        Some((parts.next()??, parts.next()??, parts.next()??))
+    });
+
+    if let Some((first, high, low)) = r_id {
+        record[107] = first as u8;
+        u32(record, 27, high << 16 | low & 0xffff);
+    }
+}
+
+impl BinCraft {
+    /// Encodes the frame in the binCraft wire format, uncompressed, so that
+    /// [`parse_adsb`](crate::parse_adsb) reads back the same frame.
+    ///
+    /// Records are always written with a 112-byte stride, in the layout of
+    /// format version 20240218 whatever `bin_craft_version` the frame was
+    /// read with. Fields the format stores scaled, such as `gs` in tenths
+    /// of a knot, are rounded to that resolution. Aircraft whose `hex` is
+    /// not a valid address, which only happens for hand-built aircraft,
+    /// have no record to write and are skipped.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let stride = STRIDE;
+        let aircraft: Vec<(&Aircraft, Icao)> =
+            self.aircraft.iter().filter_map(|aircraft| Some((aircraft, aircraft.icao()?))).collect();
+        let mut data = vec![0u8; stride * (aircraft.len() + 1)];
+
+        let now_ms = (self.now * 1e3).round() as u64;
+
+        let header = RawHeader {
+            now_low: (now_ms as u32).to_le(),
+            now_high: ((now_ms >> 32) as u32).to_le(),
+            stride: (stride as u32).to_le(),
+            global_ac_count_withpos: self.global_ac_count_withpos.to_le(),
+            globe_index: self.globe_index.to_le(),
+            south: self.south.to_le(),
+            west: self.west.to_le(),
+            north: self.north.to_le(),
+            east: self.east.to_le(),
+            messages: self.messages.to_le(),
+            receiver_lat: ((self.receiver_lat * POSITION_SCALE).round() as i32).to_le(),
+            receiver_lon: ((self.receiver_lon * POSITION_SCALE).round() as i32).to_le(),
+            bin_craft_version: BIN_CRAFT_VERSION.to_le(),
+        };
+
+        data[..HEADER_LEN].copy_from_slice(bytemuck::bytes_of(&header));
+
+        // Globe tiles of this version carry a message rate per aircraft.
+        let use_message_rate = self.globe_index != 0;
+
+        for ((aircraft, icao), record) in aircraft.into_iter().zip(data[stride..].chunks_exact_mut(stride)) {
+            encode_aircraft(aircraft, icao, use_message_rate, record);
+        }
+
+        data
+    }
+
+    /// Writes the frame as [`BinCraft::to_bytes`] encodes it, gzip
+    /// compressed, for archiving captured or merged frames compactly.
+    /// [`parse_file`](crate::parse_file) reads the result back whatever the
+    /// file is named.
+    ///
+    /// There is no zstd counterpart, although servers send zstd: the zstd
+    /// implementation this crate uses can only decompress.
+    #[cfg(feature = "gzip")]
+    pub fn write_gzip<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+
+        encoder.write_all(&self.to_bytes())?;
+        encoder.finish()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "gzip")]
+
+use std::fs::File;
+
+use adsbexchange::testutil::FrameBuilder;
+use adsbexchange::{parse_file, parse_reader, Aircraft, BinCraft, Error, SignalType, Squawk};
+
+/// A frame with a spread of aircraft, round-tripped once through the
+/// writer so every value is already at the format's resolution.
+fn frame() -> BinCraft {
+    let aircraft = (0..200).map(|i| {
+            Aircraft::new(format!("{:06x}", 0x400000 + i))
+                .with_flight(format!("TST{:04}", i))
+                .with_position(50.0 + i as f32 / 100.0, -1.0 + i as f32 / 50.0)
+                .with_alt_baro(1_000 + 25 * i)
+                .with_velocity(250.0, (i * 7 % 360) as f32)
+                .with_squawk(Squawk::from_digits([1, 2, (i % 8) as u8, 0]).unwrap())
+                .with_signal_type(SignalType::AdsbIcao)
+                .with_seen(0.5)
+    });
+
+    FrameBuilder::new(1_234.567).with_aircraft(aircraft).build()
+}
+
+#[test]
+fn gzip_round_trips_through_parse_reader() {
+    let frame = frame();
+
+    let mut compressed = Vec::new();
+    frame.write_gzip(&mut compressed).unwrap();
+
+    assert!(compressed.starts_with(&[0x1f, 0x8b]));
+    assert!(compressed.len() < frame.to_bytes().len() / 2, "{} bytes", compressed.len());
+
+    let read = parse_reader(compressed.as_slice()).unwrap();
+
+    assert_eq!(read.to_bytes(), frame.to_bytes());
+    assert_eq!(read.aircraft[199].flight.as_deref(), Some("TST0199"));
+}
+
+#[test]
+fn gzip_files_are_read_back_by_parse_file() {
+    let path = std::env::temp_dir().join(format!("adsbexchange-archive-{}.bin", std::process::id()));
+    let frame = frame();
+
+    frame.write_gzip(File::create(&path).unwrap()).unwrap();
+    let read = parse_file(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(read.unwrap().to_bytes(), frame.to_bytes());
+}
+
+#[test]
+fn a_failing_reader_is_an_io_error() {
+    let mut compressed = Vec::new();
+    frame().write_gzip(&mut compressed).unwrap();
+
+    let reset = std::io::Error::new(std::io::ErrorKind::ConnectionReset, "connection reset");
+    let reader = std::io::Read::chain(&compressed[..compressed.len() / 2], FailingReader(Some(reset)));
+
+    assert!(matches!(parse_reader(reader), Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::ConnectionReset));
+    assert!(matches!(parse_reader(&compressed[..compressed.len() / 2]), Err(Error::Decompress(_))));
+}
+
+/// Fails once with the given error.
+struct FailingReader(Option<std::io::Error>);
+
+impl std::io::Read for FailingReader {
+    fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+        self.0.take().map_or(Ok(0), Err)
+    }
+}
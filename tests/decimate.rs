@@ -0,0 +1,40 @@
+use adsbexchange::testutil::FrameBuilder;
+use adsbexchange::{Aircraft, BinCraft, DecimateStrategy};
+
+/// A frame holding the given aircraft.
+fn frame(aircraft: Vec<Aircraft>) -> BinCraft {
+    FrameBuilder::new(0.0).with_aircraft(aircraft).build_unencoded()
+}
+
+fn hexes(aircraft: Vec<&Aircraft>) -> Vec<&str> {
+    aircraft.into_iter().map(|aircraft| aircraft.hex.as_str()).collect()
+}
+
+#[test]
+fn highest_altitude_puts_ground_traffic_last() {
+    let frame = frame(vec![
+        Aircraft::new("000001").with_alt_baro(5_000),
+        Aircraft::new("000002"),
+        Aircraft::new("000003").with_alt_baro(35_000),
+        Aircraft::new("000004").with_alt_baro(12_000),
+    ]);
+
+    assert_eq!(hexes(frame.decimate(3, DecimateStrategy::HighestAltitude)), ["000003", "000004", "000001"]);
+    assert_eq!(hexes(frame.decimate(9, DecimateStrategy::HighestAltitude)).last(), Some(&"000002"));
+}
+
+#[test]
+fn grid_cell_keeps_the_freshest_per_cell() {
+    let frame = frame(vec![
+        Aircraft::new("000001").with_position(51.2, -0.4).with_seen(4.0),
+        Aircraft::new("000002").with_position(51.8, -0.1).with_seen(1.0),
+        Aircraft::new("000003").with_position(52.5, 1.5).with_seen(9.0),
+        Aircraft::new("000004").with_position(48.9, 2.3).with_seen(0.5),
+        Aircraft::new("000005"),
+    ]);
+
+    let strategy = DecimateStrategy::GridCell { degrees: 1.0 };
+
+    assert_eq!(hexes(frame.decimate(10, strategy)), ["000002", "000003", "000004"]);
+    assert_eq!(hexes(frame.decimate(2, strategy)), ["000002", "000004"]);
+}
@@ -0,0 +1,122 @@
+use adsbexchange::testutil::FrameBuilder;
+use adsbexchange::{decompress, decompress_with_limit, parse_file_with_limit, parse_reader, parse_reader_with_limit, Error};
+
+/// A zstd frame of `blocks` run-length blocks, each inflating to 128 KiB of
+/// zeros, the shape of a decompression bomb.
+fn zeros_frame(blocks: usize) -> Vec<u8> {
+    const BLOCK: u32 = 128 << 10;
+
+    // No content size, with a 1 MiB window.
+    let mut frame = vec![0x28, 0xb5, 0x2f, 0xfd, 0x00, 0x50];
+
+    for i in 0..blocks {
+        let last = (i + 1 == blocks) as u32;
+        let header = BLOCK << 3 | 1 << 1 | last;
+
+        frame.extend_from_slice(&header.to_le_bytes()[..3]);
+        frame.push(0);
+    }
+
+    frame
+}
+
+#[test]
+fn inflates_up_to_the_limit() {
+    let frame = zeros_frame(8);
+
+    assert_eq!(decompress(&frame).unwrap().len(), 1 << 20);
+    assert_eq!(decompress_with_limit(&frame, 1 << 20).unwrap().len(), 1 << 20);
+}
+
+#[test]
+fn stops_past_the_limit() {
+    let frame = zeros_frame(9);
+
+    assert!(matches!(
+        decompress_with_limit(&frame, 1 << 20),
+        Err(Error::TooLarge { limit }) if limit == 1 << 20
+    ));
+}
+
+#[test]
+fn parse_reader_applies_the_default_limit() {
+    // 72 MiB of zeros from under 3 KiB of input.
+    let frame = zeros_frame(576);
+
+    assert!(matches!(parse_reader(frame.as_slice()), Err(Error::TooLarge { .. })));
+}
+
+#[test]
+fn uncompressed_input_is_limited_too() {
+    let body = FrameBuilder::new(1.0).to_bytes();
+
+    assert!(parse_reader_with_limit(body.as_slice(), body.len()).is_ok());
+    assert!(matches!(
+        parse_reader_with_limit(body.as_slice(), body.len() - 1),
+        Err(Error::TooLarge { limit }) if limit == body.len() - 1
+    ));
+
+    let path = std::env::temp_dir().join(format!("adsbexchange-limit-{}.bin", std::process::id()));
+    std::fs::write(&path, &body).unwrap();
+    let result = parse_file_with_limit(&path, 64);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(matches!(result, Err(Error::TooLarge { limit: 64 })));
+}
+
+#[test]
+fn compressed_input_honours_a_custom_limit() {
+    let frame = zeros_frame(8);
+
+    assert!(matches!(parse_reader_with_limit(frame.as_slice(), 1 << 19), Err(Error::TooLarge { .. })));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn traces_are_limited() {
+    let trace = br#"{"icao":"4ca2d6","timestamp":1700000000.0,"trace":[]}"#;
+
+    assert!(adsbexchange::parse_trace_with_limit(trace, trace.len()).is_ok());
+    assert!(matches!(
+        adsbexchange::parse_trace_with_limit(trace, trace.len() - 1),
+        Err(Error::TooLarge { .. })
+    ));
+}
+
+/// Yields `data`, then fails the way a dropped connection does.
+struct Failing<'a>(&'a [u8]);
+
+impl std::io::Read for Failing<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.0.is_empty() {
+            return Err(std::io::Error::new(std::io::ErrorKind::ConnectionReset, "connection reset"));
+        }
+
+        let n = self.0.len().min(buf.len());
+        buf[..n].copy_from_slice(&self.0[..n]);
+        self.0 = &self.0[n..];
+
+        Ok(n)
+    }
+}
+
+#[test]
+fn reader_failures_are_io_errors() {
+    let frame = zeros_frame(8);
+
+    for len in [4, 6, 20] {
+        let result = parse_reader(Failing(&frame[..len]));
+
+        assert!(
+            matches!(&result, Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::ConnectionReset),
+            "length {}: {:?}",
+            len,
+            result.map(|frame| frame.aircraft.len()),
+        );
+    }
+
+    // Input that ends cleanly but is not valid zstd is still the decoder's.
+    let mut corrupt = frame.clone();
+    corrupt[5] = 0xff;
+    assert!(matches!(parse_reader(corrupt.as_slice()), Err(Error::Decompress(_))));
+}
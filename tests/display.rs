@@ -0,0 +1,52 @@
+use adsbexchange::{AirGround, Aircraft, Altitude, Squawk};
+
+#[test]
+fn summarises_the_known_fields() {
+    let cruising = Aircraft::new("3c6545")
+        .with_flight("DLH123")
+        .with_alt_baro(35_000)
+        .with_velocity(450.4, 269.8)
+        .with_squawk("7000".parse::<Squawk>().unwrap());
+
+    assert_eq!(cruising.to_string(), "DLH123 (3c6545) FL350 450kt 270° 7000");
+
+    let climbing = Aircraft::new("~4ca2d1").with_alt_baro(4_525).with_velocity(180.0, 5.0);
+    assert_eq!(climbing.to_string(), "~4ca2d1 4525ft 180kt 005°");
+
+    let taxiing = Aircraft::new("4ca2d1").with_alt_baro(200).with_airground(AirGround::Ground);
+    assert_eq!(taxiing.to_string(), "4ca2d1 ground");
+
+    assert_eq!(Aircraft::new("4ca2d1").to_string(), "4ca2d1");
+}
+
+#[test]
+fn altitude_prefers_ground_then_baro_then_geom() {
+    let both = Aircraft::new("4ca2d1").with_alt_baro(35_000).with_alt_geom(35_650);
+    assert_eq!(both.altitude(), Altitude::Baro(35_000));
+
+    let geom_only = Aircraft::new("4ca2d1").with_alt_geom(12_300);
+    assert_eq!(geom_only.altitude(), Altitude::Geom(12_300));
+
+    let taxiing = Aircraft::new("4ca2d1").with_alt_baro(200).with_airground(AirGround::Ground);
+    assert_eq!(taxiing.altitude(), Altitude::Ground);
+
+    let mut labelled = Aircraft::new("4ca2d1");
+    labelled.alt_baro_label = Some("ground");
+    assert_eq!(labelled.altitude(), Altitude::Ground);
+
+    assert_eq!(Aircraft::new("4ca2d1").altitude(), Altitude::Unknown);
+}
+
+#[test]
+fn altitude_delta_is_none_when_it_overflows() {
+    let mut climbing = Aircraft::new("4ca2d1").with_alt_baro(4_500);
+    climbing.nav_altitude_fms = Some(12_000);
+    assert_eq!(climbing.altitude_delta(), Some(7_500));
+
+    climbing.nav_altitude_mcp = Some(u32::MAX);
+    assert_eq!(climbing.altitude_delta(), None);
+
+    let mut deep = Aircraft::new("4ca2d1").with_alt_baro(i32::MIN);
+    deep.nav_altitude_mcp = Some(i32::MAX as u32);
+    assert_eq!(deep.altitude_delta(), None);
+}
@@ -0,0 +1,20 @@
+#![cfg(feature = "net")]
+
+use adsbexchange::{fetch_boxes, Client, Error};
+
+#[tokio::test]
+async fn fetch_boxes_without_boxes_is_an_error() {
+    let result = fetch_boxes(&Client::new(), &[]).await;
+
+    assert!(matches!(result, Err(Error::NoBoxes)), "{:?}", result.map(|frame| frame.aircraft.len()));
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test]
+async fn fetch_trace_rejects_malformed_addresses() {
+    for hex in ["4ca2é", "4ca2d", "", "~~4ca2d1"] {
+        let result = adsbexchange::fetch_trace(&Client::new(), hex, None).await;
+
+        assert!(matches!(&result, Err(Error::InvalidIcao(s)) if s == hex), "{:?}: {:?}", hex, result.map(|_| ()));
+    }
+}
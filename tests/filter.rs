@@ -0,0 +1,273 @@
+use adsbexchange::testutil::FrameBuilder;
+use adsbexchange::{Aircraft, BinCraft, PositionReport, SignalType, Squawk};
+
+/// A frame holding the given aircraft.
+fn frame(aircraft: Vec<Aircraft>) -> BinCraft {
+    FrameBuilder::new(0.0).with_aircraft(aircraft).build_unencoded()
+}
+
+#[test]
+fn primary_only_drops_rebroadcast_shadows() {
+    let frame = frame(vec![
+        Aircraft::new("a00001").with_signal_type(SignalType::AdsbIcao),
+        Aircraft::new("a00001").with_signal_type(SignalType::TisbIcao),
+        Aircraft::new("a00002").with_signal_type(SignalType::AdsrIcao),
+        Aircraft::new("a00003").with_signal_type(SignalType::AdsrIcao),
+        Aircraft::new("a00003").with_signal_type(SignalType::AdsbIcaoNt),
+        Aircraft::new("a00003").with_signal_type(SignalType::Mlat),
+    ]);
+
+    let kept = frame.primary_only()
+        .into_iter()
+        .map(|a| (a.hex.as_str(), a.signal_type.as_ref().map(SignalType::as_str)))
+        .collect::<Vec<_>>();
+
+    assert_eq!(kept, [
+        ("a00001", Some("adsb_icao")),
+        ("a00002", Some("adsr_icao")),
+        ("a00003", Some("adsb_icao_nt")),
+        ("a00003", Some("mlat")),
+    ]);
+}
+
+#[test]
+fn counts_squawks_and_emergencies() {
+    let squawk = |code: &str| code.parse::<Squawk>().unwrap();
+
+    let mut declared = Aircraft::new("a00004").with_squawk(squawk("2000"));
+    declared.emergency = Some(3);
+
+    let frame = frame(vec![
+        Aircraft::new("a00001").with_squawk(squawk("7000")),
+        Aircraft::new("a00002").with_squawk(squawk("7000")),
+        Aircraft::new("a00003").with_squawk(squawk("7700")),
+        declared,
+        Aircraft::new("a00005"),
+    ]);
+
+    let histogram = frame.squawk_histogram();
+
+    assert_eq!(histogram.len(), 3);
+    assert_eq!(histogram["7000"], 2);
+    assert_eq!(histogram["7700"], 1);
+    assert_eq!(frame.emergency_count(), 2);
+}
+
+#[test]
+fn alert_tiers_take_the_most_urgent_reason() {
+    let squawk = |code: &str| code.parse::<Squawk>().unwrap();
+    let declaring = |hex: &str, emergency: u8| {
+        let mut aircraft = Aircraft::new(hex);
+        aircraft.emergency = Some(emergency);
+        aircraft
+    };
+
+    let mut identing = Aircraft::new("a00007");
+    identing.spi = Some(true);
+
+    let mut alerting = Aircraft::new("a00008").with_squawk(squawk("7500"));
+    alerting.alert1 = Some(true);
+
+    let frame = frame(vec![
+        Aircraft::new("a00001").with_squawk(squawk("7700")),
+        declaring("a00002", 4),
+        Aircraft::new("a00003").with_squawk(squawk("7600")),
+        declaring("a00004", 5),
+        declaring("a00005", 2),
+        Aircraft::new("a00006").with_squawk(squawk("7000")),
+        identing,
+        alerting,
+        declaring("a00009", 0),
+    ]);
+
+    let tiers = frame.alert_tiers();
+    let hexes = |tier: &[&Aircraft]| tier.iter().map(|a| a.hex.clone()).collect::<Vec<_>>();
+
+    assert_eq!(hexes(&tiers.critical), ["a00001", "a00004", "a00005"]);
+    assert_eq!(hexes(&tiers.comms_loss), ["a00002", "a00003"]);
+    assert_eq!(hexes(&tiers.hijack), ["a00008"]);
+    assert_eq!(hexes(&tiers.flagged), ["a00007"]);
+    assert!(!tiers.is_empty());
+}
+
+#[test]
+fn alert_tiers_hold_one_aircraft_each() {
+    let mut unlawful = Aircraft::new("a00001");
+    unlawful.emergency = Some(5);
+
+    let mut identing = Aircraft::new("a00004");
+    identing.spi = Some(true);
+
+    let frame = frame(vec![
+        unlawful,
+        Aircraft::new("a00002").with_squawk("7600".parse().unwrap()),
+        Aircraft::new("a00003").with_squawk("7500".parse().unwrap()),
+        identing,
+    ]);
+
+    let tiers = frame.alert_tiers();
+    let hexes = |tier: &[&Aircraft]| tier.iter().map(|a| a.hex.clone()).collect::<Vec<_>>();
+
+    assert_eq!(hexes(&tiers.critical), ["a00001"]);
+    assert_eq!(hexes(&tiers.comms_loss), ["a00002"]);
+    assert_eq!(hexes(&tiers.hijack), ["a00003"]);
+    assert_eq!(hexes(&tiers.flagged), ["a00004"]);
+}
+
+#[test]
+fn duplicate_callsigns_need_two_hexes() {
+    let flying = |hex: &str, flight: &str| Aircraft::new(hex).with_flight(flight);
+
+    let frame = frame(vec![
+        flying("a00001", "BAW1"),
+        flying("a00002", "DLH4"),
+        flying("a00001", "DLH4").with_signal_type(SignalType::TisbIcao),
+        flying("a00003", "EZY5"),
+        flying("a00003", "EZY5").with_signal_type(SignalType::TisbIcao),
+        flying("a00004", "BAW1"),
+        Aircraft::new("a00005"),
+        Aircraft::new("a00006"),
+    ]);
+
+    let duplicates = frame.duplicate_callsigns()
+        .into_iter()
+        .map(|(flight, aircraft)| (flight, aircraft.iter().map(|a| a.hex.as_str()).collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(duplicates, [
+        ("BAW1".to_string(), vec!["a00001", "a00004"]),
+        ("DLH4".to_string(), vec!["a00002", "a00001"]),
+    ]);
+}
+
+#[test]
+fn high_quality_needs_every_metric() {
+    let with_quality = |hex: &str, nic: u8, rc: u16, nac_p: Option<u8>, sil: Option<u8>| {
+        let mut aircraft = Aircraft::new(hex).with_position(51.5, -0.1);
+        aircraft.quality.nic = nic;
+        aircraft.quality.rc = rc;
+        aircraft.quality.nac_p = nac_p;
+        aircraft.quality.sil = sil;
+        aircraft
+    };
+
+    let mut unpositioned = with_quality("a00007", 8, 186, Some(9), Some(3));
+    (unpositioned.lat, unpositioned.lon) = (None, None);
+
+    let frame = frame(vec![
+        with_quality("a00001", 8, 186, Some(9), Some(3)),
+        with_quality("a00002", 6, 1852, Some(9), Some(3)),
+        with_quality("a00003", 8, 186, Some(7), Some(3)),
+        with_quality("a00004", 8, 0, Some(9), Some(3)),
+        with_quality("a00005", 8, 186, None, Some(3)),
+        with_quality("a00006", 8, 186, Some(9), Some(0)),
+        unpositioned,
+        Aircraft::new("a00008").with_position(51.5, -0.1).with_signal_type(SignalType::Mlat),
+    ]);
+
+    let kept = |min_nic, min_nac_p| {
+        frame.high_quality(min_nic, min_nac_p).into_iter().map(|a| a.hex.as_str()).collect::<Vec<_>>()
+    };
+
+    assert_eq!(kept(7, 8), ["a00001"]);
+    assert_eq!(kept(6, 7), ["a00001", "a00002", "a00003"]);
+    assert_eq!(kept(0, 0), ["a00001", "a00002", "a00003"]);
+}
+
+#[test]
+fn counts_signal_types() {
+    let frame = frame(vec![
+        Aircraft::new("a00001").with_signal_type(SignalType::AdsbIcao),
+        Aircraft::new("a00002").with_signal_type(SignalType::AdsbIcao),
+        Aircraft::new("a00003").with_signal_type(SignalType::Mlat),
+        Aircraft::new("a00004").with_signal_type(SignalType::TisbTrackfile),
+        Aircraft::new("a00005"),
+    ]);
+
+    let counts = frame.signal_type_counts();
+
+    assert_eq!(counts.len(), 3);
+    assert_eq!(counts[&SignalType::AdsbIcao], 2);
+    assert_eq!(counts[&SignalType::Mlat], 1);
+    assert_eq!(counts[&SignalType::TisbTrackfile], 1);
+}
+
+#[test]
+fn trims_implausible_altitudes_at_the_bounds() {
+    let at = |hex: &str, alt_baro: i32, alt_geom: i32| Aircraft::new(hex).with_alt_baro(alt_baro).with_alt_geom(alt_geom);
+
+    let mut trimmed = frame(vec![
+        at("a00001", -2_000, 60_000),
+        at("a00002", -2_025, 60_025),
+        at("a00003", 35_000, 819_175),
+        Aircraft::new("a00004"),
+    ]);
+
+    assert_eq!(trimmed.trim_altitudes(), 3);
+
+    let altitudes = trimmed.aircraft.iter().map(|a| (a.alt_baro, a.alt_geom)).collect::<Vec<_>>();
+    assert_eq!(altitudes, [
+        (Some(-2_000), Some(60_000)),
+        (None, None),
+        (Some(35_000), None),
+        (None, None),
+    ]);
+
+    let mut narrow = frame(vec![at("a00001", 0, 45_000)]);
+
+    assert_eq!(narrow.trim_altitudes_with(0..=45_000), 0);
+    assert_eq!(narrow.trim_altitudes_with(1..=44_975), 2);
+}
+
+#[test]
+fn positions_keep_only_positioned_aircraft() {
+    let frame = frame(vec![
+        Aircraft::new("a00001").with_position(51.5, -0.1).with_alt_baro(3_500).with_velocity(180.0, 90.0).with_seen(1.5),
+        Aircraft::new("a00002").with_alt_baro(35_000),
+        Aircraft::new("a00003").with_position(52.0, 4.5),
+    ]);
+
+    assert_eq!(frame.positions(), [
+        PositionReport {
+            hex: "a00001".to_string(),
+            lat: 51.5,
+            lon: -0.1,
+            alt_baro: Some(3_500),
+            track: Some(90.0),
+            gs: Some(180.0),
+            seen_pos: Some(1.5),
+        },
+        PositionReport {
+            hex: "a00003".to_string(),
+            lat: 52.0,
+            lon: 4.5,
+            alt_baro: None,
+            track: None,
+            gs: None,
+            seen_pos: None,
+        },
+    ]);
+}
+
+#[test]
+fn sort_by_hex_puts_non_icao_addresses_last() {
+    let mut frame = frame(vec![
+        Aircraft::new("~a00001"),
+        Aircraft::new("a00002").with_alt_baro(1_000),
+        Aircraft::new("3c6444"),
+        Aircraft::new("a00002").with_alt_baro(2_000),
+        Aircraft::new("a00001"),
+    ]);
+
+    frame.sort_by_hex();
+
+    let sorted = frame.aircraft.iter().map(|a| (a.hex.as_str(), a.alt_baro)).collect::<Vec<_>>();
+
+    assert_eq!(sorted, [
+        ("3c6444", None),
+        ("a00001", None),
+        ("a00002", Some(1_000)),
+        ("a00002", Some(2_000)),
+        ("~a00001", None),
+    ]);
+}
@@ -0,0 +1,133 @@
+use adsbexchange::testutil::FrameBuilder;
+use adsbexchange::{AirGround, Aircraft};
+
+fn at(lat: f32, lon: f32) -> Aircraft {
+    Aircraft::new("000001").with_position(lat, lon)
+}
+
+fn assert_bearing(aircraft: &Aircraft, expected: f64) {
+    let bearing = aircraft.bearing_from(0.0, 0.0).unwrap();
+
+    assert!((bearing - expected).abs() < 1e-6, "expected {}, got {}", expected, bearing);
+}
+
+#[test]
+fn bearing_cardinal_directions() {
+    assert_bearing(&at(1.0, 0.0), 0.0);
+    assert_bearing(&at(0.0, 1.0), 90.0);
+    assert_bearing(&at(-1.0, 0.0), 180.0);
+    assert_bearing(&at(0.0, -1.0), 270.0);
+}
+
+#[test]
+fn bearing_is_normalized() {
+    let bearing = at(1.0, -1.0).bearing_from(0.0, 0.0).unwrap();
+
+    assert!((0.0..360.0).contains(&bearing));
+    assert!(bearing > 270.0);
+}
+
+#[test]
+fn bearing_without_position() {
+    assert_eq!(Aircraft::default().bearing_from(0.0, 0.0), None);
+}
+
+#[test]
+fn close_pairs_respects_both_separations() {
+    // 1 and 2 are 3 NM and 500 ft apart; 3 is level with 1 but 6 NM away;
+    // 4 is next to 2 but 2,000 ft higher; 5 is on the ground.
+    let frame = FrameBuilder::new(0.0)
+        .with_aircraft([
+            Aircraft::new("000001").with_position(50.0, 0.0).with_alt_baro(10_000),
+            Aircraft::new("000002").with_position(50.05, 0.0).with_alt_baro(10_500),
+            Aircraft::new("000003").with_position(49.9, 0.0).with_alt_baro(10_000),
+            Aircraft::new("000004").with_position(50.05, 0.01).with_alt_baro(12_500),
+            Aircraft::new("000005").with_position(50.0, 0.001).with_airground(AirGround::Ground),
+        ])
+        .build_unencoded();
+
+    let pairs = frame.close_pairs(5.0, 1_000)
+        .into_iter()
+        .map(|(a, b)| (a.hex.as_str(), b.hex.as_str()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(pairs, [("000001", "000002")]);
+}
+
+#[test]
+fn coverage_buckets_by_compass_sector() {
+    let mut frame = FrameBuilder::new(0.0).build_unencoded();
+    assert_eq!(frame.coverage(), None);
+
+    frame.receiver_lat = 0.0;
+    frame.receiver_lon = 10.0;
+    frame.aircraft = vec![
+        Aircraft::new("000001").with_position(1.0, 10.0),
+        Aircraft::new("000002").with_position(2.0, 10.1),
+        Aircraft::new("000003").with_position(0.0, 11.0),
+        Aircraft::new("000004").with_position(-1.0, 9.9),
+        Aircraft::new("000005").with_position(0.5, 9.95),
+        Aircraft::new("000006"),
+    ];
+
+    let sectors = frame.coverage().unwrap();
+
+    assert_eq!(sectors[0].count, 3);
+    assert!((sectors[0].max_range_nm.unwrap() - 120.2).abs() < 0.1);
+    assert_eq!(sectors[4].count, 1);
+    assert_eq!(sectors[8].count, 1);
+    assert_eq!(sectors[1], Default::default());
+    assert_eq!(sectors.iter().map(|sector| sector.count).sum::<usize>(), 5);
+}
+
+#[test]
+fn wind_triangle_residual() {
+    // 400 kt heading east with a 50 kt northerly drifts south of east.
+    let mut aircraft = at(0.0, 0.0);
+    aircraft.tas = Some(400);
+    aircraft.true_heading = Some(90.0);
+    aircraft.gs = Some(403.11);
+    aircraft.track = Some(97.125);
+    aircraft.wd = Some(360);
+    aircraft.ws = Some(50);
+
+    assert!(aircraft.wind_triangle_residual().unwrap() < 0.1);
+
+    aircraft.wd = Some(180);
+    assert!((aircraft.wind_triangle_residual().unwrap() - 100.0).abs() < 0.1);
+
+    aircraft.tas = None;
+    assert_eq!(aircraft.wind_triangle_residual(), None);
+}
+
+#[test]
+fn geohash_of_known_points() {
+    // The worked example from the original geohash description, "u4pruydqqvj",
+    // as far as an f32 position carries it.
+    assert_eq!(at(57.64911, 10.40744).geohash(9).as_deref(), Some("u4pruydqq"));
+    assert_eq!(at(51.4700, -0.4543).geohash(6).as_deref(), Some("gcpsv9"));
+    assert_eq!(at(-33.9461, 151.1772).geohash(5).as_deref(), Some("r3gwb"));
+
+    // A shorter hash is a prefix of a longer one for the same point.
+    let long = at(51.4700, -0.4543).geohash(9).unwrap();
+    assert!(long.starts_with("gcpsv9"));
+
+    assert_eq!(at(0.0, 0.0).geohash(0).as_deref(), Some(""));
+    assert_eq!(at(0.0, 0.0).geohash(40).unwrap().len(), 12);
+}
+
+#[test]
+fn geohash_without_position() {
+    assert_eq!(Aircraft::new("000001").geohash(6), None);
+}
+
+#[test]
+fn coordinates_need_both_halves() {
+    assert_eq!(at(51.5, -0.25).coordinates(), Some((51.5, -0.25)));
+
+    let mut half = at(51.5, -0.25);
+    half.lon = None;
+
+    assert_eq!(half.coordinates(), None);
+    assert_eq!(Aircraft::new("000001").coordinates(), None);
+}
@@ -0,0 +1,55 @@
+use adsbexchange::{BoundingBox, Error, GlobeIndex};
+
+fn index() -> GlobeIndex {
+    GlobeIndex::new(3.0, vec![BoundingBox::new(60.0, 90.0, 150.0, -130.0)]).unwrap()
+}
+
+#[test]
+fn grid_tile_round_trips() {
+    let index = index();
+    let tile = index.index_of(51.5, -0.1);
+
+    assert_eq!(tile, 47 * 121 + 59 + 1000);
+    assert_eq!(index.bounds(tile), Some(BoundingBox::new(51.0, 54.0, -3.0, 0.0)));
+}
+
+#[test]
+fn special_tile_across_antimeridian() {
+    let index = index();
+
+    assert_eq!(index.index_of(70.0, 179.0), 0);
+    assert_eq!(index.index_of(70.0, -140.0), 0);
+    assert_ne!(index.index_of(70.0, -120.0), 0);
+    assert_eq!(index.bounds(0), Some(BoundingBox::new(60.0, 90.0, 150.0, -130.0)));
+    assert_eq!(index.bounds(1), None);
+}
+
+#[test]
+fn tiles_covering_box() {
+    let index = index();
+
+    assert_eq!(index.tiles_covering(&BoundingBox::new(51.5, 51.5, -0.1, -0.1)), [index.index_of(51.5, -0.1)]);
+    assert_eq!(index.tiles_covering(&BoundingBox::new(50.0, 55.0, -1.0, 1.0)).len(), 6);
+    assert_eq!(index.tiles_covering(&BoundingBox::new(61.0, 64.0, 170.0, -170.0)), [0]);
+}
+
+#[test]
+fn grid_must_be_positive_and_finite() {
+    for grid in [0.0, -3.0, f64::NAN, f64::INFINITY] {
+        let result = GlobeIndex::new(grid, Vec::new());
+
+        assert!(matches!(result, Err(Error::InvalidGrid { .. })), "grid {}: {:?}", grid, result);
+    }
+}
+
+#[test]
+fn non_finite_boxes_cover_no_tiles() {
+    let index = index();
+
+    for edge in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        assert!(index.tiles_covering(&BoundingBox::new(edge, 55.0, -1.0, 1.0)).is_empty());
+        assert!(index.tiles_covering(&BoundingBox::new(50.0, edge, -1.0, 1.0)).is_empty());
+        assert!(index.tiles_covering(&BoundingBox::new(50.0, 55.0, edge, 1.0)).is_empty());
+        assert!(index.tiles_covering(&BoundingBox::new(50.0, 55.0, -1.0, edge)).is_empty());
+    }
+}
@@ -0,0 +1,30 @@
+use std::collections::HashSet;
+
+use adsbexchange::{Aircraft, Icao};
+
+#[test]
+fn parses_and_displays_like_readsb() {
+    let icao = "4CA2D6".parse::<Icao>().unwrap();
+    let non_icao = "~4ca2d6".parse::<Icao>().unwrap();
+
+    assert_eq!(icao.to_string(), "4ca2d6");
+    assert_eq!(non_icao.to_string(), "~4ca2d6");
+    assert_eq!(non_icao.address(), icao.address());
+    assert!(non_icao.is_non_icao());
+    assert_eq!(Icao::from_raw(0x0100_0abc | 1 << 28), "~000abc".parse().unwrap());
+
+    for invalid in ["", "4ca2d", "4ca2d6f", "~~4ca2d6", "+4ca2d", "4ca2dg"] {
+        assert!(invalid.parse::<Icao>().is_err(), "{:?}", invalid);
+    }
+}
+
+#[test]
+fn keys_sets_by_address_and_flag() {
+    let keys = ["4ca2d6", "4CA2D6", "~4ca2d6"]
+        .into_iter()
+        .filter_map(|hex| Aircraft::new(hex).icao())
+        .collect::<HashSet<_>>();
+
+    assert_eq!(keys.len(), 2);
+    assert_eq!(Aircraft::new("bogus").icao(), None);
+}
@@ -0,0 +1,60 @@
+use adsbexchange::testutil::FrameBuilder;
+use adsbexchange::{Aircraft, BinCraft, BoundingBox};
+
+fn frame(positions: &[(f32, f32)]) -> BinCraft {
+    let positioned = positions
+        .iter()
+        .enumerate()
+        .map(|(i, &(lat, lon))| Aircraft::new(format!("{:06x}", i)).with_position(lat, lon));
+
+    FrameBuilder::new(0.0).with_aircraft(positioned.chain([Aircraft::new("ffffff")])).build_unencoded()
+}
+
+fn hexes(aircraft: &[&Aircraft]) -> Vec<String> {
+    aircraft.iter().map(|a| a.hex.clone()).collect()
+}
+
+#[test]
+fn in_bbox_keeps_frame_order() {
+    let frame = frame(&[(51.9, 0.5), (50.0, -3.0), (51.0, -0.5), (52.5, 1.0), (52.0, 1.0)]);
+    let index = frame.build_index();
+
+    let found = index.in_bbox(&BoundingBox::new(51.0, 52.0, -1.0, 1.0));
+
+    assert_eq!(hexes(&found), ["000000", "000002", "000004"]);
+}
+
+#[test]
+fn in_bbox_across_the_antimeridian() {
+    let frame = frame(&[(10.0, 179.5), (10.0, -179.5), (10.0, 0.0), (10.0, 170.0)]);
+    let index = frame.build_index();
+
+    assert_eq!(hexes(&index.in_bbox(&BoundingBox::new(5.0, 15.0, 175.0, -175.0))), ["000000", "000001"]);
+    assert_eq!(hexes(&index.in_bbox(&BoundingBox::new(-90.0, 90.0, -180.0, 180.0))).len(), 4);
+}
+
+#[test]
+fn within_radius_is_nearest_first() {
+    // About 60, 30, 120 and 6 NM north of the point, and one 12 NM east
+    // across the antimeridian.
+    let frame = frame(&[(1.0, 179.9), (0.5, 179.9), (2.0, 179.9), (0.1, 179.9), (0.0, -179.9)]);
+    let index = frame.build_index();
+
+    assert_eq!(hexes(&index.within_radius(0.0, 179.9, 65.0)), ["000003", "000004", "000001", "000000"]);
+    assert!(index.within_radius(0.0, 179.9, 1.0).is_empty());
+}
+
+#[test]
+fn nearest_matches_a_full_scan() {
+    let positions = (0..200)
+        .map(|i| ((i * 37 % 170) as f32 - 85.0 + i as f32 / 1000.0, (i * 71 % 359) as f32 - 179.5))
+        .collect::<Vec<_>>();
+    let frame = frame(&positions);
+    let index = frame.build_index();
+
+    for (lat, lon) in [(0.0, 0.0), (51.5, -0.1), (-89.0, 179.9), (89.9, 45.0)] {
+        for n in [1, 5, 50, 250] {
+            assert_eq!(hexes(&index.nearest(lat, lon, n)), hexes(&frame.nearest(lat, lon, n)), "{}, {} n={}", lat, lon, n);
+        }
+    }
+}
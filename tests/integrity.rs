@@ -0,0 +1,66 @@
+use adsbexchange::{Aircraft, Integrity, PositionSource, QualityMetrics, SignalType};
+
+fn quality(adsb_version: u8) -> QualityMetrics {
+    QualityMetrics {
+        rc: 186,
+        nac_p: Some(9),
+        sil: Some(3),
+        sil_type: 2,
+        adsb_version,
+        ..QualityMetrics::default()
+    }
+}
+
+#[test]
+fn version_0_resolves_only_containment() {
+    assert_eq!(
+        quality(0).resolved_integrity(),
+        Integrity { containment_radius_m: Some(186.0), ..Integrity::default() },
+    );
+}
+
+#[test]
+fn version_1_leaves_sil_basis_unknown() {
+    let integrity = quality(1).resolved_integrity();
+
+    assert_eq!(integrity.position_accuracy_m, Some(30.0));
+    assert_eq!(integrity.sil_probability, Some(1e-7));
+    assert_eq!(integrity.sil_per_sample, None);
+}
+
+#[test]
+fn version_2_resolves_everything() {
+    assert_eq!(
+        quality(2).resolved_integrity(),
+        Integrity {
+            containment_radius_m: Some(186.0),
+            position_accuracy_m: Some(30.0),
+            sil_probability: Some(1e-7),
+            sil_per_sample: Some(true),
+        },
+    );
+
+    let unknown = QualityMetrics { nac_p: Some(0), sil: Some(0), ..quality(2) };
+
+    assert_eq!(unknown.resolved_integrity().position_accuracy_m, None);
+    assert_eq!(unknown.resolved_integrity().sil_probability, None);
+}
+
+#[test]
+fn position_source_follows_signal_type_and_nic() {
+    let positioned = |signal_type: SignalType, nic: u8| {
+        let mut aircraft = Aircraft::new("400001").with_position(51.5, -0.1).with_signal_type(signal_type);
+        aircraft.quality.nic = nic;
+        aircraft.position_source()
+    };
+
+    assert_eq!(positioned(SignalType::AdsbIcao, 8), Some(PositionSource::Adsb));
+    assert_eq!(positioned(SignalType::AdsbOther, 0), Some(PositionSource::AdsbUnverified));
+    assert_eq!(positioned(SignalType::AdsrIcao, 8), Some(PositionSource::Adsr));
+    assert_eq!(positioned(SignalType::TisbTrackfile, 0), Some(PositionSource::Tisb));
+    assert_eq!(positioned(SignalType::Mlat, 0), Some(PositionSource::Mlat));
+    assert_eq!(positioned(SignalType::Adsc, 8), Some(PositionSource::Other));
+
+    assert_eq!(Aircraft::new("400001").with_position(51.5, -0.1).position_source(), Some(PositionSource::Other));
+    assert_eq!(Aircraft::new("400001").with_signal_type(SignalType::AdsbIcao).position_source(), None);
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "serde")]
+
+use adsbexchange::testutil::FrameBuilder;
+use adsbexchange::{parse_adsb, AirGround, Aircraft, Category, JsonOptions, PositionReport, SignalType, Squawk};
+
+#[test]
+fn deserializes_tar1090_aircraft() {
+    let aircraft: Aircraft = serde_json::from_str(r#"{
+        "hex": "4ca2d6", "type": "adsb_icao", "flight": "RYR8TN  ", "r": "EI-DWF", "t": "B738",
+        "alt_baro": 36000, "alt_geom": 36525, "gs": 451.3, "squawk": "2243", "emergency": "none",
+        "category": "A3", "nav_modes": ["autopilot", "lnav", "bogus"], "lat": 52.1, "lon": 4.3,
+        "nic": 8, "rc": 186, "version": 2, "nac_p": null, "sil_type": "perhour",
+        "mlat": [], "tisb": [], "messages": 1234, "seen": 0.2, "rssi": -21.5
+    }"#).unwrap();
+
+    assert_eq!(aircraft.hex, "4ca2d6");
+    assert!(matches!(aircraft.signal_type, Some(SignalType::AdsbIcao)));
+    assert_eq!(aircraft.flight.as_deref(), Some("RYR8TN"));
+    assert_eq!(aircraft.alt_baro, Some(36000));
+    assert_eq!(aircraft.airground, AirGround::Airborne);
+    assert_eq!(aircraft.squawk, "2243".parse::<Squawk>().ok());
+    assert_eq!(aircraft.category, Some(Category::Large));
+    assert_eq!(aircraft.nav_modes, ["autopilot", "lnav"]);
+    assert_eq!(aircraft.quality.nac_p, None);
+    assert_eq!(aircraft.quality.sil_type, 1);
+    assert_eq!(aircraft.ias, None);
+}
+
+#[test]
+fn deserializes_ground_altitude() {
+    let aircraft: Aircraft = serde_json::from_str(r#"{"hex": "a1b2c3", "alt_baro": "ground"}"#).unwrap();
+
+    assert!(aircraft.is_on_ground());
+    assert_eq!(aircraft.alt_baro, None);
+    assert_eq!(aircraft.alt_baro_label, Some("ground"));
+}
+
+#[test]
+fn round_trips_through_to_json() {
+    let mut original = Aircraft::new("a1b2c3")
+        .with_position(51.5, -0.1)
+        .with_alt_baro(3500)
+        .with_flight("BAW123")
+        .with_category(Category::Heavy);
+    original.message_rate = Some(3.7);
+
+    let parsed: Aircraft = serde_json::from_value(original.to_json()).unwrap();
+
+    assert_eq!(parsed.to_json(), original.to_json());
+}
+
+#[test]
+fn globe_tile_message_rates_round_trip() {
+    let mut data = vec![0u8; 224];
+    data[8..12].copy_from_slice(&112u32.to_le_bytes());
+    data[16..20].copy_from_slice(&1u32.to_le_bytes());
+    data[40..44].copy_from_slice(&20240218u32.to_le_bytes());
+    data[112 + 62..112 + 64].copy_from_slice(&37u16.to_le_bytes());
+
+    let tile = parse_adsb(&data).unwrap();
+    let json = tile.aircraft[0].to_json();
+
+    assert_eq!(json["messageRate"], 3.7f32);
+    assert!(json.get("messages").is_none());
+
+    let parsed: Aircraft = serde_json::from_value(json.clone()).unwrap();
+
+    assert_eq!(parsed.message_rate, Some(3.7));
+    assert_eq!(parsed.to_json(), json);
+}
+
+#[test]
+fn writes_one_line_per_aircraft() {
+    let frame = FrameBuilder::new(1.5)
+        .with_aircraft([Aircraft::new("000001").with_alt_baro(1_000), Aircraft::new("000002")])
+        .build_unencoded();
+
+    let mut out = Vec::new();
+    frame.write_ndjson(&mut out).unwrap();
+
+    let lines = String::from_utf8(out).unwrap();
+    let lines = lines.lines().map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()).collect::<Vec<_>>();
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["hex"], "000001");
+    assert_eq!(lines[0]["alt_baro"], 1_000);
+    assert_eq!(lines[1]["now"], 1.5);
+}
+
+#[test]
+fn rounds_coordinates() {
+    let aircraft = Aircraft::new("000001").with_position(51.5, -0.123456);
+
+    assert_eq!(aircraft.to_json()["lat"], 51.5);
+    assert_eq!(aircraft.to_json()["lon"], -0.12346);
+
+    let exact = aircraft.to_json_with(&JsonOptions { coordinate_decimals: 6 });
+
+    assert_eq!(exact["lon"], -0.123456);
+}
+
+#[test]
+fn position_reports_serialize_without_missing_fields() {
+    let aircraft = Aircraft::new("4ca2d6").with_position(52.25, 4.5).with_alt_baro(36_000);
+    let report = PositionReport::from_aircraft(&aircraft).unwrap();
+
+    assert_eq!(
+        serde_json::to_string(&report).unwrap(),
+        r#"{"hex":"4ca2d6","lat":52.25,"lon":4.5,"alt_baro":36000}"#,
+    );
+}
@@ -0,0 +1,49 @@
+use adsbexchange::testutil::FrameBuilder;
+use adsbexchange::{AirGround, Aircraft, BinCraft, SignalType};
+
+/// A frame holding the given aircraft.
+fn frame(aircraft: Vec<Aircraft>) -> BinCraft {
+    FrameBuilder::new(0.0).with_aircraft(aircraft).build_unencoded()
+}
+
+#[test]
+fn places_airborne_aircraft_at_their_altitude() {
+    let kml = frame(vec![
+        Aircraft::new("3c6545")
+            .with_flight("DLH123")
+            .with_position(50.05, 8.57)
+            .with_alt_baro(35_000)
+            .with_velocity(450.0, 270.0)
+            .with_signal_type(SignalType::AdsbIcao),
+    ])
+    .to_kml();
+
+    assert!(kml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<kml xmlns=\"http://www.opengis.net/kml/2.2\">"));
+    assert!(kml.ends_with("</Document>\n</kml>\n"));
+    assert!(kml.contains("<name>DLH123</name>"));
+    assert!(kml.contains(
+        "<Point><extrude>1</extrude><altitudeMode>absolute</altitudeMode>\
+         <coordinates>8.570000,50.049999,10668</coordinates></Point>"
+    ));
+    assert!(kml.contains(
+        "<description>Hex: 3c6545&lt;br/&gt;Altitude: 35000 ft&lt;br/&gt;Ground speed: 450 kt\
+         &lt;br/&gt;Track: 270°&lt;br/&gt;Source: adsb_icao</description>"
+    ));
+}
+
+#[test]
+fn clamps_grounded_aircraft_and_skips_unpositioned_ones() {
+    let mut parked = Aircraft::new("4ca2d1")
+        .with_position(51.47, -0.45)
+        .with_alt_baro(75)
+        .with_airground(AirGround::Ground);
+    parked.registration = "G-A&B".to_string();
+
+    let kml = frame(vec![parked, Aircraft::new("4ca2d2").with_flight("NOPOS1")]).to_kml();
+
+    assert_eq!(kml.matches("<Placemark>").count(), 1);
+    assert!(kml.contains("<name>4ca2d1</name>"));
+    assert!(kml.contains("<altitudeMode>clampToGround</altitudeMode><coordinates>-0.450000,51.470001</coordinates>"));
+    assert!(kml.contains("Registration: G-A&amp;amp;B"));
+    assert!(!kml.contains("NOPOS1"));
+}
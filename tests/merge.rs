@@ -0,0 +1,75 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use adsbexchange::testutil::FrameBuilder;
+use adsbexchange::{Aircraft, BinCraft, MergePolicy, SignalType};
+
+/// A frame taken at `now` holding the given aircraft.
+fn frame(now: f64, aircraft: Vec<Aircraft>) -> BinCraft {
+    FrameBuilder::new(now).with_aircraft(aircraft).build_unencoded()
+}
+
+#[test]
+fn merge_newest_keeps_the_latest_report() {
+    let mut west = frame(100.0, vec![
+        Aircraft::new("000001").with_position(51.0, -0.5).with_seen(1.0),
+        Aircraft::new("000002").with_position(51.0, -0.1).with_seen(3.0),
+    ]);
+
+    // Heard at 99 and 101 here, against 99 and 97 in the first frame.
+    let east = frame(102.0, vec![
+        Aircraft::new("000001").with_position(51.0, -0.4).with_seen(3.0),
+        Aircraft::new("000002").with_position(51.0, 0.0).with_seen(1.0),
+        Aircraft::new("000003").with_position(51.0, 0.5).with_seen(0.5),
+    ]);
+
+    west.merge_newest(east);
+
+    let merged = west.aircraft
+        .iter()
+        .map(|a| (a.hex.as_str(), a.lon, a.seen))
+        .collect::<Vec<_>>();
+
+    assert_eq!(west.now, 102.0);
+    assert_eq!(merged, [
+        ("000001", Some(-0.5), Some(3.0)),
+        ("000002", Some(0.0), Some(1.0)),
+        ("000003", Some(0.5), Some(0.5)),
+    ]);
+}
+
+#[test]
+fn merge_shifts_ages_to_the_later_frame() {
+    // The older frame is merged into the newer one and the other way round;
+    // either way every age ends up relative to 110.
+    for older_first in [true, false] {
+        let older = frame(100.0, vec![
+            Aircraft::new("000001").with_position(51.0, -0.5).with_seen(1.0).with_signal_type(SignalType::AdsbIcao),
+            Aircraft::new("000002").with_position(51.0, -0.1).with_seen(2.0).with_signal_type(SignalType::Mlat),
+        ]);
+
+        let newer = frame(110.0, vec![
+            Aircraft::new("000002").with_position(51.0, 0.0).with_seen(1.0).with_signal_type(SignalType::AdsbIcao),
+            Aircraft::new("000003").with_position(51.0, 0.5).with_seen(0.5).with_signal_type(SignalType::Mlat),
+        ]);
+
+        let (mut merged, other) = if older_first { (older, newer) } else { (newer, older) };
+        merged.merge(other, &MergePolicy::default());
+        merged.sort_by_hex();
+
+        let ages = merged.aircraft
+            .iter()
+            .map(|a| (a.hex.as_str(), a.seen, a.seen_pos))
+            .collect::<Vec<_>>();
+
+        assert_eq!(merged.now, 110.0);
+        assert_eq!(ages, [
+            ("000001", Some(11.0), Some(11.0)),
+            ("000002", Some(1.0), Some(1.0)),
+            ("000003", Some(0.5), Some(0.5)),
+        ]);
+
+        // Still heard at 99, as in the older frame.
+        let heard = merged.aircraft[0].position_time(&merged).unwrap();
+        assert_eq!(heard, UNIX_EPOCH + Duration::from_secs(99));
+    }
+}
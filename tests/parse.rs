@@ -0,0 +1,475 @@
+use adsbexchange::{decode_record, iter_frames, parse_adsb, parse_adsb_with, parse_header, Aircraft, BinCraft, Error, FieldGroups};
+
+const STRIDE: usize = 112;
+
+/// A record for hex `400001` with every validity bit set and a position at
+/// 51.5, -0.1.
+fn record() -> [u8; STRIDE] {
+    let mut record = [0u8; STRIDE];
+
+    record[0..4].copy_from_slice(&0x400001u32.to_le_bytes());
+    record[8..12].copy_from_slice(&(-100_000i32).to_le_bytes());
+    record[12..16].copy_from_slice(&51_500_000i32.to_le_bytes());
+    record[73..78].fill(0xff);
+
+    record
+}
+
+fn frame(records: &[[u8; STRIDE]]) -> Vec<u8> {
+    let mut data = vec![0u8; STRIDE];
+
+    data[8..12].copy_from_slice(&(STRIDE as u32).to_le_bytes());
+    data[40..44].copy_from_slice(&20240218u32.to_le_bytes());
+
+    for record in records {
+        data.extend_from_slice(record);
+    }
+
+    data
+}
+
+#[test]
+fn decodes_position() {
+    let frame = parse_adsb(&frame(&[record()])).unwrap();
+    let aircraft = &frame.aircraft[0];
+
+    assert_eq!(aircraft.hex, "400001");
+    assert_eq!(aircraft.lat, Some(51.5));
+    assert_eq!(aircraft.lon, Some(-0.1));
+}
+
+#[test]
+fn unset_position_is_none() {
+    let mut unset = record();
+    unset[12..16].copy_from_slice(&i32::MAX.to_le_bytes());
+
+    // Also with the nogps flag, which makes the decoder force the position
+    // validity bit on.
+    let mut nogps = unset;
+    nogps[106] = 1;
+
+    let frame = parse_adsb(&frame(&[unset, nogps])).unwrap();
+
+    for aircraft in &frame.aircraft {
+        assert_eq!(aircraft.lat, None);
+        assert_eq!(aircraft.lon, None);
+        assert_eq!(aircraft.seen_pos, None);
+    }
+}
+
+#[test]
+fn decodes_header_fields() {
+    let mut data = frame(&[]);
+
+    data[0..4].copy_from_slice(&1_500u32.to_le_bytes());
+    data[4..8].copy_from_slice(&1u32.to_le_bytes());
+    data[12..16].copy_from_slice(&9_876u32.to_le_bytes());
+    data[16..20].copy_from_slice(&1_234u32.to_le_bytes());
+    data[20..22].copy_from_slice(&(-10i16).to_le_bytes());
+    data[22..24].copy_from_slice(&(-20i16).to_le_bytes());
+    data[24..26].copy_from_slice(&30i16.to_le_bytes());
+    data[26..28].copy_from_slice(&40i16.to_le_bytes());
+    data[28..32].copy_from_slice(&55_555u32.to_le_bytes());
+    data[32..36].copy_from_slice(&47_123_456i32.to_le_bytes());
+    data[36..40].copy_from_slice(&(-122_654_321i32).to_le_bytes());
+
+    let frame = parse_adsb(&data).unwrap();
+
+    assert_eq!(frame.now, 1.5 + 4294967.296);
+    assert_eq!(frame.stride, STRIDE as u32);
+    assert_eq!(frame.bin_craft_version, 20240218);
+    assert_eq!(frame.global_ac_count_withpos, 9_876);
+    assert_eq!(frame.globe_index, 1_234);
+    assert_eq!((frame.south, frame.west, frame.north, frame.east), (-10, -20, 30, 40));
+    assert_eq!(frame.messages, 55_555);
+    assert_eq!((frame.receiver_lat, frame.receiver_lon), (47.123456, -122.654321));
+    assert!(frame.aircraft.is_empty());
+}
+
+#[test]
+fn parse_header_counts_records() {
+    let mut data = frame(&[record(), record(), record()]);
+    data[28..32].copy_from_slice(&55_555u32.to_le_bytes());
+
+    let header = parse_header(&data).unwrap();
+
+    assert_eq!(header.messages, 55_555);
+    assert_eq!(header.aircraft_count, 3);
+
+    // A partial trailing record is not counted, and not an error here.
+    assert_eq!(parse_header(&data[..data.len() - 1]).unwrap().aircraft_count, 2);
+}
+
+#[test]
+fn short_bodies_are_truncated() {
+    let full = frame(&[record()]);
+
+    for len in [0, 10, 43, 44, 60, STRIDE - 1, STRIDE + 50] {
+        let result = parse_adsb(&full[..len]);
+
+        assert!(
+            matches!(result, Err(Error::Truncated { available, .. }) if available == len),
+            "length {}: {:?}",
+            len,
+            result.map(|frame| frame.aircraft.len()),
+        );
+    }
+}
+
+#[test]
+fn decode_record_matches_parse_adsb() {
+    let data = frame(&varied_records());
+    let ctx = parse_header(&data).unwrap().context(FieldGroups::ALL);
+
+    let records = data[STRIDE..]
+        .chunks(ctx.stride() as usize)
+        .map(|record| decode_record(record, &ctx))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    let mut frame = parse_adsb(&data).unwrap();
+    let first = frame.aircraft[0].hex.clone();
+    frame.aircraft = records;
+
+    assert_eq!(decoded_fields(frame), decoded_fields(parse_adsb(&data).unwrap()));
+
+    // Only the first stride is read; a short slice is an error, not a panic.
+    assert_eq!(decode_record(&data[STRIDE..], &ctx).unwrap().hex, first);
+    assert!(matches!(
+        decode_record(&data[STRIDE..STRIDE * 2 - 1], &ctx),
+        Err(Error::Truncated { needed: STRIDE, available }) if available == STRIDE - 1
+    ));
+}
+
+#[test]
+fn tiny_stride_is_rejected() {
+    let mut data = frame(&[]);
+    data[8..12].copy_from_slice(&40u32.to_le_bytes());
+
+    assert!(matches!(parse_adsb(&data), Err(Error::InvalidStride { stride: 40 })));
+}
+
+#[test]
+fn short_strides_skip_trailing_fields() {
+    let mut record = varied_records()[1];
+    record[104] = 3;
+
+    // A frame from an older server whose records end before the tail.
+    let mut data = vec![0u8; 88];
+    data[8..12].copy_from_slice(&88u32.to_le_bytes());
+    data.extend_from_slice(&record[..88]);
+
+    let frame = parse_adsb(&data).unwrap();
+    let aircraft = &frame.aircraft[0];
+
+    assert_eq!(aircraft.flight.as_deref(), Some("TST123"));
+    assert_eq!(aircraft.db_flags, 1);
+    assert_eq!(aircraft.tail, "");
+    assert_eq!(aircraft.registration, "");
+    assert_eq!(aircraft.receiver_count, 0);
+    assert_eq!(aircraft.extra_flags, 0);
+    assert_eq!(aircraft.r_id, None);
+}
+
+/// Records with every field populated and varied, including a non-ICAO
+/// address, a missing position and partially cleared validity bits.
+fn varied_records() -> Vec<[u8; STRIDE]> {
+    (0..64u32)
+        .map(|i| {
+            let mut record = [0u8; STRIDE];
+
+            record[0..4].copy_from_slice(&(0x4ca000 + i + ((i % 5 == 0) as u32) * (1 << 24)).to_le_bytes());
+            record[4..6].copy_from_slice(&(i as u16 * 3).to_le_bytes());
+            record[6..8].copy_from_slice(&(i as u16).to_le_bytes());
+            record[8..12].copy_from_slice(&(-179_123_457 + i as i32 * 5_432_101).to_le_bytes());
+            record[12..16].copy_from_slice(&if i == 7 { i32::MAX } else { -89_876_543 + i as i32 * 2_765_432 }.to_le_bytes());
+
+            for (field, word) in record[16..64].chunks_exact_mut(2).enumerate() {
+                word.copy_from_slice(&((i as u16 * 97 + field as u16 * 31) % 4000).to_le_bytes());
+            }
+
+            record[32..34].copy_from_slice(&0x7421u16.to_le_bytes());
+            record[64] = 0xa0 + (i % 8) as u8;
+            record[65] = (i % 12) as u8;
+            record[66] = (i % 64) as u8;
+            record[67] = ((i % 13) as u8) << 4 | (i % 7) as u8;
+            record[68] = ((i % 4) as u8) << 4 | (i % 4) as u8;
+            record[69] = 0x21;
+            record[70] = 0x12;
+            record[71] = 0xa9;
+            record[72] = (i * 37 % 256) as u8;
+            record[73..78].fill(0xff);
+            record[73] &= !((i % 3 == 0) as u8) << 3;
+            record[74] ^= (i % 256) as u8;
+            record[78..86].copy_from_slice(b"TST123  ");
+            record[86..88].copy_from_slice(&(i as u16 % 4).to_le_bytes());
+            record[88..92].copy_from_slice(b"A20N");
+            record[92..100].copy_from_slice(b"D-AIXY\0\0");
+            record[104] = (i % 9) as u8;
+            record[105] = (i * 4 % 256) as u8;
+            record[106] = (i % 2) as u8;
+            record[107] = i as u8;
+            record[108..112].copy_from_slice(&(0x12345678u32 + i).to_le_bytes());
+
+            record
+        })
+        .collect()
+}
+
+/// The frame's decoded fields, without the source records `raw-bytes` keeps.
+fn decoded_fields(frame: BinCraft) -> String {
+    #[cfg(feature = "raw-bytes")]
+    let frame = {
+        let mut frame = frame;
+        frame.aircraft.iter_mut().for_each(|aircraft| aircraft.raw.clear());
+        frame
+    };
+
+    format!("{:?}", frame)
+}
+
+#[test]
+fn to_bytes_round_trips() {
+    let mut data = frame(&varied_records());
+    data[0..4].copy_from_slice(&1_234_567u32.to_le_bytes());
+    data[4..8].copy_from_slice(&395u32.to_le_bytes());
+    data[28..32].copy_from_slice(&987_654u32.to_le_bytes());
+
+    let parsed = parse_adsb(&data).unwrap();
+    let encoded = parsed.to_bytes();
+    let reparsed = parse_adsb(&encoded).unwrap();
+
+    assert_eq!(reparsed.to_bytes(), encoded);
+    assert_eq!(decoded_fields(reparsed), decoded_fields(parsed));
+}
+
+#[test]
+fn to_bytes_rounds_to_the_nearest_step() {
+    let mut aircraft = Aircraft::new("400001").with_alt_baro(-1_013).with_alt_geom(38);
+    aircraft.baro_rate = Some(-1_020);
+    aircraft.geom_rate = Some(-1_019);
+    aircraft.nav_altitude_mcp = Some(35_006);
+
+    let mut frame = parse_adsb(&frame(&[])).unwrap();
+    frame.aircraft = vec![aircraft];
+
+    let read = parse_adsb(&frame.to_bytes()).unwrap();
+    let aircraft = &read.aircraft[0];
+
+    // Steps of 25 ft, 8 ft/min and 4 ft, with halves away from zero.
+    assert_eq!((aircraft.alt_baro, aircraft.alt_geom), (Some(-1_025), Some(50)));
+    assert_eq!((aircraft.baro_rate, aircraft.geom_rate), (Some(-1_024), Some(-1_016)));
+    assert_eq!(aircraft.nav_altitude_mcp, Some(35_008));
+}
+
+#[test]
+fn to_bytes_skips_invalid_addresses_and_writes_the_full_stride() {
+    // A frame from a server with a longer stride than this crate writes.
+    let mut data = vec![0u8; 120];
+    data[8..12].copy_from_slice(&120u32.to_le_bytes());
+    data.extend_from_slice(&record());
+    data.extend_from_slice(&[0u8; 8]);
+
+    let mut frame = parse_adsb(&data).unwrap();
+    frame.aircraft.push(Aircraft::new("not-hex"));
+    frame.aircraft.push(Aircraft::new("~4ca2d6"));
+
+    let encoded = frame.to_bytes();
+    let read = parse_adsb(&encoded).unwrap();
+
+    assert_eq!(encoded.len(), STRIDE * 3);
+    assert_eq!(read.stride, STRIDE as u32);
+    assert_eq!(read.aircraft.iter().map(|aircraft| aircraft.hex.as_str()).collect::<Vec<_>>(), ["400001", "~4ca2d6"]);
+}
+
+#[test]
+fn ident_and_alert_need_their_validity_bits() {
+    let mut valid = record();
+    valid[73] = 0b110;
+
+    let mut invalid = valid;
+    invalid[76] &= !16;
+    invalid[77] &= !8;
+
+    let frame = parse_adsb(&frame(&[valid, invalid])).unwrap();
+
+    assert!(frame.aircraft[0].is_identing());
+    assert!(frame.aircraft[0].is_alerting());
+    assert!(!frame.aircraft[1].is_identing());
+    assert!(!frame.aircraft[1].is_alerting());
+}
+
+#[test]
+fn field_groups_limit_decoding() {
+    let data = frame(&varied_records());
+
+    let full = parse_adsb(&data).unwrap();
+    let core = parse_adsb_with(&data, FieldGroups::NONE).unwrap();
+    let velocity = parse_adsb_with(&data, FieldGroups::VELOCITY | FieldGroups::QUALITY).unwrap();
+
+    for ((full, core), velocity) in full.aircraft.iter().zip(&core.aircraft).zip(&velocity.aircraft) {
+        assert_eq!(core.hex, full.hex);
+        assert_eq!((core.lat, core.lon, core.seen_pos), (full.lat, full.lon, full.seen_pos));
+        assert_eq!((core.alt_baro, core.airground), (full.alt_baro, full.airground));
+        assert_eq!((core.gs, core.flight.as_deref(), core.quality.nac_p), (None, None, None));
+
+        assert_eq!((velocity.gs, velocity.quality.nac_p), (full.gs, full.quality.nac_p));
+        assert_eq!((velocity.squawk, velocity.nav_qnh), (None, None));
+    }
+}
+
+#[test]
+fn globe_tiles_carry_a_fractional_message_rate() {
+    let mut tile = record();
+    tile[62..64].copy_from_slice(&37u16.to_le_bytes());
+
+    let mut data = frame(&[tile]);
+    data[16..20].copy_from_slice(&1_234u32.to_le_bytes());
+
+    let frame = parse_adsb(&data).unwrap();
+
+    assert_eq!(frame.aircraft[0].message_rate, Some(3.7));
+    assert_eq!(frame.aircraft[0].messages, None);
+    assert_eq!(parse_adsb(&frame.to_bytes()).unwrap().aircraft[0].message_rate, Some(3.7));
+}
+
+#[test]
+fn nogps_is_only_bit_0_of_extra_flags() {
+    let mut reserved = record();
+    reserved[106] = 0xfe;
+
+    let mut lost = record();
+    lost[106] = 0x01;
+
+    let frame = parse_adsb(&frame(&[reserved, lost])).unwrap();
+
+    assert_eq!((frame.aircraft[0].extra_flags, frame.aircraft[0].nogps), (0xfe, 0));
+    assert!(!frame.aircraft[0].is_gps_lost());
+    assert_eq!((frame.aircraft[1].extra_flags, frame.aircraft[1].nogps), (0x01, 1));
+    assert!(frame.aircraft[1].is_gps_lost());
+}
+
+#[test]
+fn zero_rssi_and_sentinel_angles_stay_finite() {
+    let mut records = Vec::new();
+
+    for sentinel in [i16::MIN, i16::MAX, -1, 0] {
+        let mut record = record();
+
+        for field in 14..=23 {
+            record[field * 2..field * 2 + 2].copy_from_slice(&sentinel.to_le_bytes());
+        }
+
+        record[62..64].copy_from_slice(&u16::MAX.to_le_bytes());
+        record[104] = 1;
+        record[105] = 0;
+
+        records.push(record);
+    }
+
+    let mut data = frame(&records);
+    data[16..20].copy_from_slice(&1_234u32.to_le_bytes());
+
+    for aircraft in parse_adsb(&data).unwrap().aircraft {
+        assert!((aircraft.rssi - -49.49).abs() < 0.01, "rssi {}", aircraft.rssi);
+
+        let floats = [
+            aircraft.seen, aircraft.seen_pos, aircraft.gs, aircraft.mach, aircraft.roll, aircraft.track,
+            aircraft.track_rate, aircraft.mag_heading, aircraft.true_heading, aircraft.nav_heading,
+            aircraft.message_rate,
+        ];
+
+        for value in floats {
+            assert!(value.is_some_and(f32::is_finite), "{:?}", floats);
+        }
+
+        // No sentinel is a plausible altimeter setting.
+        assert_eq!(aircraft.nav_qnh, None);
+    }
+}
+
+#[test]
+fn non_octal_squawks_are_dropped() {
+    let mut valid = record();
+    valid[32..34].copy_from_slice(&0x7421u16.to_le_bytes());
+
+    let mut invalid = valid;
+    invalid[32..34].copy_from_slice(&0x7a21u16.to_le_bytes());
+
+    let frame = parse_adsb(&frame(&[valid, invalid])).unwrap();
+
+    assert_eq!(frame.aircraft[0].squawk.map(|squawk| squawk.to_string()).as_deref(), Some("7421"));
+    assert_eq!(frame.aircraft[1].squawk, None);
+}
+
+#[test]
+fn implausible_qnh_is_dropped() {
+    let records = [10_132i16, 9_650, 0, -10_132, 12_000].map(|raw| {
+        let mut record = record();
+        record[28..30].copy_from_slice(&raw.to_le_bytes());
+        record
+    });
+
+    let frame = parse_adsb(&frame(&records)).unwrap();
+    let qnh = frame.aircraft.iter().map(|aircraft| aircraft.nav_qnh).collect::<Vec<_>>();
+
+    assert_eq!(qnh, [Some(1013.2), Some(965.0), None, None, None]);
+
+    let inhg = frame.aircraft[0].nav_qnh_inhg().unwrap();
+    assert!((inhg - 29.92).abs() < 0.005, "{}", inhg);
+}
+
+#[test]
+fn concatenated_frames_are_split_at_each_header() {
+    let at = |now_ms: u32, records: &[[u8; STRIDE]]| {
+        let mut data = frame(records);
+        data[0..4].copy_from_slice(&now_ms.to_le_bytes());
+        data
+    };
+
+    let capture = [at(1_000, &[record(), record()]), at(2_000, &[]), at(3_000, &[record()])].concat();
+    let frames = iter_frames(&capture).collect::<Result<Vec<_>, _>>().unwrap();
+
+    let summary = frames.iter().map(|frame| (frame.now, frame.aircraft.len())).collect::<Vec<_>>();
+    assert_eq!(summary, [(1.0, 2), (2.0, 0), (3.0, 1)]);
+
+    // A single body is a capture of one frame.
+    let single = frame(&[record()]);
+    assert_eq!(iter_frames(&single).count(), 1);
+}
+
+#[test]
+fn concatenated_frames_stop_at_a_truncated_one() {
+    let mut capture = [frame(&[record()]), frame(&[record()])].concat();
+    capture.truncate(capture.len() - 10);
+
+    let frames = iter_frames(&capture).collect::<Vec<_>>();
+
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].as_ref().unwrap().aircraft.len(), 1);
+    assert!(matches!(frames[1], Err(Error::Truncated { .. })));
+}
+
+#[test]
+fn pre_2022_globe_tiles_carry_a_message_count() {
+    // A globe tile from a server older than version 20220916, with records
+    // ending after the registration.
+    let mut record = record();
+    record[62..64].copy_from_slice(&37u16.to_le_bytes());
+    record[92..98].copy_from_slice(b"G-ABCD");
+
+    let mut data = vec![0u8; 104];
+    data[8..12].copy_from_slice(&104u32.to_le_bytes());
+    data[16..20].copy_from_slice(&1_234u32.to_le_bytes());
+    data[40..44].copy_from_slice(&20220801u32.to_le_bytes());
+    data.extend_from_slice(&record[..104]);
+
+    let frame = parse_adsb(&data).unwrap();
+    let aircraft = &frame.aircraft[0];
+
+    assert_eq!((frame.bin_craft_version, frame.globe_index), (20220801, 1_234));
+    assert_eq!((aircraft.messages, aircraft.message_rate), (Some(37), None));
+    assert_eq!(aircraft.registration, "G-ABCD");
+    assert_eq!((aircraft.lat, aircraft.lon), (Some(51.5), Some(-0.1)));
+    assert_eq!(aircraft.receiver_count, 0);
+}
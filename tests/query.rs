@@ -0,0 +1,36 @@
+use adsbexchange::{BoundingBox, Compression, DbFlag, QueryBuilder, Squawk};
+
+#[test]
+fn selections() {
+    let bbox = BoundingBox::new(-10.5, 30.0, 170.0, -170.0);
+
+    assert_eq!(QueryBuilder::bbox(&bbox).query(), "binCraft&zstd&box=-10.5,30,170,-170");
+    assert_eq!(QueryBuilder::circle(51.5, -0.1, 25.0).query(), "binCraft&zstd&circle=51.5,-0.1,25");
+    assert_eq!(QueryBuilder::closest(51.5, -0.1, 25.0).query(), "binCraft&zstd&closest=51.5,-0.1,25");
+    assert_eq!(QueryBuilder::hex(&["4ca2d1", "~abc123"]).query(), "binCraft&zstd&find_hex=4ca2d1,~abc123");
+    assert_eq!(QueryBuilder::callsign(&["BAW1"]).query(), "binCraft&zstd&find_callsign=BAW1");
+    assert_eq!(QueryBuilder::registration(&["G-EUPT"]).query(), "binCraft&zstd&find_reg=G-EUPT");
+    assert_eq!(QueryBuilder::type_code(&["A20N", "B38M"]).query(), "binCraft&zstd&find_type=A20N,B38M");
+    assert_eq!(QueryBuilder::all().query(), "binCraft&zstd&all");
+    assert_eq!(QueryBuilder::all_with_pos().query(), "binCraft&zstd&all_with_pos");
+}
+
+#[test]
+fn filters_follow_the_selection() {
+    let query = QueryBuilder::all_with_pos()
+        .with_compression(Compression::None)
+        .with_squawk("7700".parse::<Squawk>().unwrap())
+        .with_callsign_prefix("BAW")
+        .with_db_flag(DbFlag::Pia)
+        .with_below_alt_baro(5_000)
+        .with_positioned_only()
+        .with_db_flag(DbFlag::Ladd)
+        .with_db_flag(DbFlag::Pia)
+        .with_param("jv2", None);
+
+    assert_eq!(
+        query.url("http://my-pi:8080/re-api/"),
+        "http://my-pi:8080/re-api/?binCraft&all_with_pos&filter_squawk=7700&filter_callsign_prefix=BAW\
+         &below_alt_baro=5000&filter_with_pos&jv2&filter_dbFlag=pia,ladd",
+    );
+}
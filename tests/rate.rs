@@ -0,0 +1,48 @@
+use adsbexchange::testutil::FrameBuilder;
+use adsbexchange::{parse_adsb, Activity, Aircraft, BinCraft, MessageStats};
+
+fn frame(aircraft: Vec<Aircraft>) -> BinCraft {
+    FrameBuilder::new(0.0).with_messages(1_000).with_aircraft(aircraft).build_unencoded()
+}
+
+#[test]
+fn message_summaries() {
+    let counted = |messages: u16| {
+        let mut aircraft = Aircraft::new("a00001");
+        aircraft.messages = Some(messages);
+        aircraft
+    };
+
+    let frame = frame(vec![counted(10), counted(40), counted(1), Aircraft::new("a00004")]);
+
+    assert_eq!(frame.avg_messages_per_aircraft(), Some(250.0));
+    assert_eq!(frame.message_count_stats(), Some(MessageStats { count: 3, min: 1.0, max: 40.0, mean: 17.0 }));
+    assert_eq!(frame.message_rate_stats(), None);
+
+    let empty = self::frame(Vec::new());
+
+    assert_eq!(empty.avg_messages_per_aircraft(), None);
+    assert_eq!(empty.message_count_stats(), None);
+}
+
+#[test]
+fn activity_follows_what_the_frame_carries() {
+    // A record with a raw value of 37 in the shared count/rate slot.
+    let body = |globe_index: u32| {
+        let mut data = vec![0u8; 224];
+        data[8..12].copy_from_slice(&112u32.to_le_bytes());
+        data[16..20].copy_from_slice(&globe_index.to_le_bytes());
+        data[40..44].copy_from_slice(&20240218u32.to_le_bytes());
+        data[112..116].copy_from_slice(&0x400001u32.to_le_bytes());
+        data[174..176].copy_from_slice(&37u16.to_le_bytes());
+        data
+    };
+
+    let tile = parse_adsb(&body(1_234)).unwrap();
+    assert_eq!(tile.aircraft[0].activity(), Activity::Rate(3.7));
+
+    let snapshot = parse_adsb(&body(0)).unwrap();
+    assert_eq!(snapshot.aircraft[0].activity(), Activity::Count(37));
+
+    assert_eq!(Aircraft::new("400001").activity(), Activity::Unknown);
+}
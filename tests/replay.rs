@@ -0,0 +1,24 @@
+#![cfg(feature = "stream")]
+
+use std::time::Duration;
+
+use adsbexchange::testutil::FrameBuilder;
+use adsbexchange::replay;
+use futures_util::StreamExt;
+
+#[tokio::test(start_paused = true)]
+async fn tiny_speeds_wait_a_day_at_most() {
+    let dir = std::env::temp_dir().join(format!("adsbexchange-replay-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("1.bin"), FrameBuilder::new(1.0).to_bytes()).unwrap();
+    std::fs::write(dir.join("2.bin"), FrameBuilder::new(2.0).to_bytes()).unwrap();
+
+    let started = tokio::time::Instant::now();
+    let frames = replay(&dir, f64::MIN_POSITIVE).collect::<Vec<_>>().await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let times = frames.into_iter().map(|frame| frame.unwrap().now).collect::<Vec<_>>();
+
+    assert_eq!(times, [1.0, 2.0]);
+    assert_eq!(started.elapsed(), Duration::from_secs(86_400));
+}
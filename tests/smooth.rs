@@ -0,0 +1,55 @@
+use adsbexchange::testutil::FrameBuilder;
+use adsbexchange::{Aircraft, BinCraft, PositionSmoother};
+
+/// A frame at `now_ms` milliseconds holding one aircraft positioned just
+/// now.
+fn frame(now_ms: u32, lat: f32, lon: f32) -> BinCraft {
+    FrameBuilder::new(now_ms as f64 / 1e3)
+        .with_aircraft([Aircraft::new("4ca2d1").with_position(lat, lon).with_seen(0.0)])
+        .build_unencoded()
+}
+
+#[test]
+fn smooths_jitter_on_a_straight_track() {
+    let mut smoother = PositionSmoother::default();
+    let (mut raw_error, mut smoothed_error) = (0.0, 0.0);
+
+    // Northbound at 0.001°/s, with 0.005° of alternating MLAT jitter.
+    for second in 0..60u32 {
+        let truth = 50.0 + second as f32 * 0.001;
+        let jitter = if second % 2 == 0 { 0.005 } else { -0.005 };
+
+        let mut frame = frame(second * 1_000, truth + jitter, 8.0);
+        smoother.update(&mut frame);
+
+        if second >= 20 {
+            raw_error += jitter.abs();
+            smoothed_error += (frame.aircraft[0].lat.unwrap() - truth).abs();
+        }
+    }
+
+    assert!(smoothed_error < raw_error / 2.0, "{} vs {}", smoothed_error, raw_error);
+
+    let icao = "4ca2d1".parse().unwrap();
+    let (lat, _) = smoother.position_at(icao, 60.0).unwrap();
+
+    assert!((lat - 50.06).abs() < 0.004, "{}", lat);
+}
+
+#[test]
+fn restarts_after_a_gap_and_across_the_antimeridian() {
+    let mut smoother = PositionSmoother::default();
+
+    let mut first = frame(0, 10.0, 179.999);
+    smoother.update(&mut first);
+    assert_eq!(first.aircraft[0].lon, Some(179.999));
+
+    let mut crossed = frame(1_000, 10.0, -179.999);
+    smoother.update(&mut crossed);
+    let lon = crossed.aircraft[0].lon.unwrap();
+    assert!(lon.abs() > 179.99, "{}", lon);
+
+    let mut later = frame(120_000, 20.0, 0.0);
+    smoother.update(&mut later);
+    assert_eq!((later.aircraft[0].lat, later.aircraft[0].lon), (Some(20.0), Some(0.0)));
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "stream")]
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use adsbexchange::testutil::FrameBuilder;
+use adsbexchange::{stream_source, AircraftSource, BinCraft, Error, Fixture, PollInterval, StreamEvent};
+use futures_util::StreamExt;
+
+/// An empty frame at the given time, as a binCraft body.
+fn body(now_ms: u32) -> Vec<u8> {
+    FrameBuilder::new(now_ms as f64 / 1e3).to_bytes()
+}
+
+#[tokio::test]
+async fn fixture_repeats_its_last_body() {
+    let fixture = Fixture::new(vec![body(1_000), body(2_000)]);
+
+    let mut times = Vec::new();
+
+    for _ in 0..3 {
+        times.push(fixture.snapshot().await.unwrap().now);
+    }
+
+    assert_eq!(times, [1.0, 2.0, 2.0]);
+}
+
+#[tokio::test]
+async fn empty_fixture_fails_every_snapshot() {
+    let fixture = Fixture::new(Vec::new());
+
+    for _ in 0..2 {
+        assert!(matches!(fixture.snapshot().await, Err(Error::Truncated { available: 0, .. })));
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn stream_source_reports_failures_then_frames() {
+    let fixture = Fixture::new(vec![vec![0; 10], body(1_000)]);
+    let events = stream_source(fixture, Duration::ZERO).take(3).collect::<Vec<_>>().await;
+
+    assert!(matches!(events[0], StreamEvent::Reconnecting { attempt: 1, last_frame: None, .. }));
+    assert!(matches!(events[1], StreamEvent::Connected));
+    assert!(matches!(&events[2], StreamEvent::Frame(frame) if frame.now == 1.0));
+}
+
+#[tokio::test(start_paused = true)]
+async fn reconnecting_keeps_the_last_frame() {
+    let fixture = Fixture::new(vec![body(1_000), vec![0; 10], vec![0; 10], body(2_000)]);
+    let events = stream_source(fixture, Duration::ZERO).take(6).collect::<Vec<_>>().await;
+
+    let last_frames = events[2..4]
+        .iter()
+        .map(|event| match event {
+            StreamEvent::Reconnecting { last_frame, .. } => last_frame.as_ref().map(|frame| frame.now),
+            event => panic!("{:?}", event),
+        })
+        .collect::<Vec<_>>();
+
+    assert!(matches!(&events[1], StreamEvent::Frame(frame) if frame.now == 1.0));
+    assert_eq!(last_frames, [Some(1.0), Some(1.0)]);
+    assert!(matches!(events[4], StreamEvent::Connected));
+    assert!(matches!(&events[5], StreamEvent::Frame(frame) if frame.now == 2.0));
+}
+
+#[tokio::test(start_paused = true)]
+async fn adaptive_polling_follows_the_server_cadence() {
+    let fixture = Fixture::new([1_000, 1_000, 3_000, 3_000, 5_000].map(body).to_vec());
+    let interval = PollInterval::Adaptive { min: Duration::from_millis(100), max: Duration::from_secs(10) };
+
+    let started = tokio::time::Instant::now();
+    let mut times = Vec::new();
+
+    let events = stream_source(fixture, interval).take(4);
+    futures_util::pin_mut!(events);
+
+    while let Some(event) = events.next().await {
+        if let StreamEvent::Frame(frame) = event {
+            times.push((frame.now, started.elapsed().as_millis()));
+        }
+    }
+
+    // Repeated frames are skipped and retried after `min`; once the 2 s
+    // cadence is known, the next fetch waits for it.
+    assert_eq!(times, [(1.0, 0), (3.0, 200), (5.0, 2_300)]);
+}
+
+/// A source reporting the given frame times in turn, then the last again.
+struct Times(std::sync::Mutex<Vec<f64>>);
+
+impl AircraftSource for Times {
+    async fn snapshot(&self) -> Result<BinCraft, Error> {
+        let mut times = self.0.lock().unwrap();
+        let now = if times.len() > 1 { times.remove(0) } else { times[0] };
+
+        Ok(FrameBuilder::new(now).build_unencoded())
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn adaptive_polling_survives_absurd_frame_times() {
+    let source = Times(std::sync::Mutex::new(vec![1.0, 1e300, f64::INFINITY, f64::NAN, f64::NAN]));
+    let interval = PollInterval::Adaptive { min: Duration::from_millis(100), max: Duration::from_secs(10) };
+
+    let frames = stream_source(source, interval)
+        .filter_map(|event| async move {
+            match event {
+                StreamEvent::Frame(frame) => Some(frame.now),
+                _ => None,
+            }
+        })
+        .take(4)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(frames[..3], [1.0, 1e300, f64::INFINITY]);
+    assert!(frames[3].is_nan());
+}
+
+/// A source whose snapshots never complete, recording when one is dropped.
+struct Stuck(Arc<AtomicBool>);
+
+struct SetOnDrop(Arc<AtomicBool>);
+
+impl Drop for SetOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+impl AircraftSource for Stuck {
+    async fn snapshot(&self) -> Result<BinCraft, Error> {
+        let _guard = SetOnDrop(self.0.clone());
+
+        std::future::pending().await
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn dropping_the_stream_cancels_the_fetch_in_flight() {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let mut events = Box::pin(stream_source(Stuck(dropped.clone()), Duration::from_secs(1)));
+
+    assert!(tokio::time::timeout(Duration::from_secs(5), events.next()).await.is_err());
+    assert!(!dropped.load(Ordering::SeqCst));
+
+    drop(events);
+
+    assert!(dropped.load(Ordering::SeqCst));
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "test-util")]
+
+use adsbexchange::testutil::{synthetic_aircraft, FrameBuilder};
+use adsbexchange::{parse_adsb, Aircraft, BoundingBox};
+
+#[test]
+fn same_seed_gives_the_same_frame() {
+    let bbox = BoundingBox::new(50.0, 52.0, -1.0, 1.0);
+
+    let frame = |seed| FrameBuilder::new(1_000.0).with_aircraft(synthetic_aircraft(seed, 50, &bbox)).to_bytes();
+
+    assert_eq!(frame(7), frame(7));
+    assert_ne!(frame(7), frame(8));
+}
+
+#[test]
+fn synthetic_aircraft_stay_inside_the_box() {
+    for bbox in [BoundingBox::new(50.0, 52.0, -1.0, 1.0), BoundingBox::new(-20.0, -10.0, 175.0, -170.0)] {
+        let frame = FrameBuilder::new(1_000.0).with_aircraft(synthetic_aircraft(1, 200, &bbox)).build();
+
+        assert_eq!(frame.aircraft.len(), 200);
+        assert_eq!(frame.aircraft[0].hex, "400000");
+        assert_eq!(frame.aircraft[199].flight.as_deref(), Some("TST0199"));
+
+        for aircraft in &frame.aircraft {
+            let (lat, lon) = (aircraft.lat.unwrap() as f64, aircraft.lon.unwrap() as f64);
+
+            assert!(bbox.contains(lat, lon), "{} {} outside {:?}", lat, lon, bbox);
+            assert!(aircraft.squawk.unwrap().is_valid());
+        }
+    }
+}
+
+#[test]
+fn build_matches_parsing_the_bytes() {
+    let frame = || {
+        FrameBuilder::new(1_700_000_000.5)
+            .with_messages(12_345)
+            .with_receiver(51.5, -0.1)
+            .with_aircraft([Aircraft::new("4ca2d1").with_position(51.47, -0.45).with_alt_baro(3_500).with_seen(1.0)])
+    };
+
+    let built = frame().build();
+    let parsed = parse_adsb(&frame().to_bytes()).unwrap();
+
+    assert_eq!(built.now, 1_700_000_000.5);
+    assert_eq!(built.messages, parsed.messages);
+    assert_eq!(built.messages, 12_345);
+    assert_eq!(built.aircraft[0].hex, parsed.aircraft[0].hex);
+    assert_eq!(built.aircraft[0].lat, parsed.aircraft[0].lat);
+    assert_eq!(built.aircraft[0].alt_baro, Some(3_500));
+    assert_eq!(built.aircraft[0].seen, Some(1.0));
+}
+
+#[test]
+fn advance_moves_aircraft_along_their_track() {
+    let aircraft = Aircraft::new("4ca2d1").with_position(50.0, 8.0).with_velocity(360.0, 0.0).with_seen(0.0);
+    let parked = Aircraft::new("4ca2d2").with_position(50.5, 8.5);
+
+    let frame = FrameBuilder::new(1_000.0).with_aircraft([aircraft, parked]).advance(60.0).build();
+
+    // 360 kt for a minute is 6 NM, a tenth of a degree north.
+    assert_eq!(frame.now, 1_060.0);
+    assert!((frame.aircraft[0].lat.unwrap() - 50.1).abs() < 0.001);
+    assert!((frame.aircraft[0].lon.unwrap() - 8.0).abs() < 0.001);
+    assert_eq!(frame.aircraft[1].lat, Some(50.5));
+}
@@ -0,0 +1,86 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use adsbexchange::testutil::FrameBuilder;
+use adsbexchange::{Aircraft, BinCraft, MergePolicy};
+
+/// A frame with no aircraft, taken at `now`.
+fn frame_at(now: f64) -> BinCraft {
+    FrameBuilder::new(now).build_unencoded()
+}
+
+#[test]
+fn position_time_subtracts_seen_pos() {
+    let frame = frame_at(1_700_000_000.0);
+    let aircraft = Aircraft::new("400001").with_position(51.5, -0.1).with_seen(2.5);
+
+    assert_eq!(
+        aircraft.position_time(&frame),
+        Some(UNIX_EPOCH + Duration::from_millis(1_699_999_997_500)),
+    );
+}
+
+#[test]
+fn position_time_without_position() {
+    let frame = frame_at(1_700_000_000.0);
+    let aircraft = Aircraft::new("400001").with_seen(2.5);
+
+    assert_eq!(aircraft.position_time(&frame), None);
+}
+
+#[test]
+fn age_measures_against_the_local_clock() {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs_f64();
+
+    let age = frame_at(now - 3.0).age().unwrap();
+    assert!(age >= Duration::from_secs(3) && age < Duration::from_secs(60), "{:?}", age);
+
+    // A server clock ahead of ours is no latency at all.
+    assert_eq!(frame_at(now + 60.0).age(), Some(Duration::ZERO));
+}
+
+#[test]
+fn unrepresentable_frame_times_have_no_timestamp() {
+    let aircraft = Aircraft::new("400001").with_position(51.5, -0.1).with_seen(2.5);
+
+    for now in [f64::INFINITY, f64::NEG_INFINITY, f64::NAN, -1.0, 1e300] {
+        let mut frame = frame_at(now);
+        frame.fetched_at = Some(SystemTime::now());
+
+        assert_eq!(frame.timestamp(), None, "{}", now);
+        assert_eq!(frame.age(), None);
+        assert_eq!(frame.fetch_latency(), None);
+        assert_eq!(aircraft.position_time(&frame), None);
+    }
+
+    assert_eq!(frame_at(0.0).timestamp(), Some(UNIX_EPOCH));
+}
+
+#[test]
+fn fetch_latency_needs_a_fetch_time() {
+    let mut frame = frame_at(1_700_000_000.0);
+    assert_eq!(frame.fetched_at, None);
+    assert_eq!(frame.fetch_latency(), None);
+
+    frame.fetched_at = Some(UNIX_EPOCH + Duration::from_millis(1_700_000_001_250));
+    assert_eq!(frame.fetch_latency(), Some(Duration::from_millis(1_250)));
+
+    frame.fetched_at = Some(UNIX_EPOCH + Duration::from_secs(1_699_999_999));
+    assert_eq!(frame.fetch_latency(), Some(Duration::ZERO));
+}
+
+#[test]
+fn merging_keeps_the_later_fetch_time() {
+    let later = UNIX_EPOCH + Duration::from_secs(1_700_000_002);
+
+    let mut first = frame_at(1_700_000_000.0);
+    first.fetched_at = Some(UNIX_EPOCH + Duration::from_secs(1_700_000_001));
+
+    let mut second = frame_at(1_700_000_000.0);
+    second.fetched_at = Some(later);
+
+    first.merge_newest(second);
+    assert_eq!(first.fetched_at, Some(later));
+
+    first.merge(frame_at(1_700_000_000.0), &MergePolicy::default());
+    assert_eq!(first.fetched_at, Some(later));
+}
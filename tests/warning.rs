@@ -0,0 +1,43 @@
+use adsbexchange::testutil::FrameBuilder;
+use adsbexchange::{BinCraft, BoundingBox, Warning};
+
+/// An empty frame whose header reports covering the given whole degrees.
+fn served(south: i16, north: i16, west: i16, east: i16) -> BinCraft {
+    FrameBuilder::new(0.0).with_served_box(south, north, west, east).build()
+}
+
+#[test]
+fn served_box_comes_from_the_header() {
+    assert_eq!(served(50, 52, -1, 1).served_box(), Some(BoundingBox::new(50.0, 52.0, -1.0, 1.0)));
+    assert_eq!(served(0, 0, 0, 0).served_box(), None);
+}
+
+#[test]
+fn warns_when_an_edge_moved_beyond_the_tolerance() {
+    let requested = BoundingBox::new(30.0, 70.0, -30.0, 40.0);
+    let clamped = served(40, 60, -10, 20);
+
+    assert_eq!(
+        clamped.box_clamp(&requested, 1.0),
+        Some(Warning::BoxClamped { requested, served: BoundingBox::new(40.0, 60.0, -10.0, 20.0) }),
+    );
+    assert_eq!(clamped.box_clamp(&requested, f64::INFINITY), None);
+
+    // Whole-degree rounding of the requested edges is within the default.
+    let rounded = BoundingBox::new(50.4, 51.6, -0.5, 0.9);
+    assert_eq!(served(50, 52, -1, 1).box_clamp(&rounded, 1.0), None);
+    assert!(served(50, 52, -1, 1).box_clamp(&rounded, 0.1).is_some());
+
+    // Nothing to compare against.
+    assert_eq!(served(0, 0, 0, 0).box_clamp(&requested, 1.0), None);
+}
+
+#[test]
+fn clamped_boxes_describe_both_areas() {
+    let warning = Warning::BoxClamped {
+        requested: BoundingBox::new(30.0, 70.0, -30.0, 40.0),
+        served: BoundingBox::new(40.0, 60.0, -10.0, 20.0),
+    };
+
+    assert_eq!(warning.to_string(), "requested box 30,70,-30,40 but the server covered 40,60,-10,20");
+}